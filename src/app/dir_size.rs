@@ -0,0 +1,269 @@
+// Directory Size
+
+use std::{
+    fs,
+    thread,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+};
+
+use ratatui::text::Span;
+
+use super::{App, FileSaver};
+use crate::error::{AppResult, ErrorType};
+use crate::rt_error;
+
+/// The size is None when the computation is cancelled.
+pub type SizeResponse = (PathBuf, Option<u64>);
+
+/// Compute total sizes of directories in background, the results are cached per path.
+#[derive(Default)]
+pub struct DirSize {
+    cache: HashMap<PathBuf, u64>,
+
+    /// Directories being computed.
+    pending: HashSet<PathBuf>,
+
+    /// Whether current directory is sorted by total size, with bars of disk usage.
+    usage_mode: bool,
+
+    /// Current directory, whose change cancels the computations requested before.
+    scope: PathBuf,
+
+    /// Increased when scope changes, the requests of previous generations are dropped.
+    generation: Arc<AtomicUsize>,
+    request_sender: Option<Sender<(PathBuf, usize)>>,
+}
+
+impl DirSize {
+    pub fn usage_mode(&self) -> bool {
+        self.usage_mode
+    }
+
+    /// Get the total size of FILE in DIR, None if it's a directory not computed yet.
+    pub fn total(&self, dir: &Path, file: &FileSaver) -> Option<u64> {
+        if file.is_dir {
+            self.cache.get(&dir.join(&file.name)).copied()
+        } else {
+            Some(file.size())
+        }
+    }
+
+    /// The size shown in state line, a directory shows its total size once computed.
+    pub fn size_span<'a>(&self, dir: &Path, file: &FileSaver) -> Span<'a> {
+        if !file.is_dir {
+            return file.size_span()
+        }
+
+        let path = dir.join(&file.name);
+        match self.cache.get(&path) {
+            Some(size) => Span::raw(file_size::fit_4(*size)),
+            None if self.pending.contains(&path) => Span::raw("..."),
+            None => file.size_span()
+        }
+    }
+
+    /// Sort FILES in DIR by total size, the largest one comes first.
+    pub fn sort(&self, dir: &Path, files: &mut [FileSaver]) {
+        files.sort_by(|a, b| {
+            self.total(dir, b).unwrap_or(0)
+                .cmp(&self.total(dir, a).unwrap_or(0))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+    }
+
+    /// Compute size of PATH unless it's computed, or FORCE to compute it again.
+    fn request(&mut self, path: PathBuf, force: bool) {
+        if self.pending.contains(&path) || !force && self.cache.contains_key(&path) {
+            return
+        }
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let sent = self.request_sender.as_ref()
+            .is_some_and(|sender| sender.send((path.to_owned(), generation)).is_ok());
+        if sent {
+            self.pending.insert(path);
+        }
+    }
+
+    fn set_scope(&mut self, path: PathBuf) {
+        if self.scope != path {
+            self.scope = path;
+            self.generation.fetch_add(1, Ordering::Release);
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn init_dir_size(&mut self) -> Receiver<SizeResponse> {
+        let (request_tx, request_rx) = mpsc::channel::<(PathBuf, usize)>();
+        let (size_tx, size_rx)       = mpsc::channel::<SizeResponse>();
+        let generation = Arc::clone(&self.dir_size.generation);
+
+        thread::spawn(move || {
+            while let Ok((path, request_generation)) = request_rx.recv() {
+                // Stop walking once current directory is changed,
+                // and the queued requests out of date are dropped quickly.
+                let cancelled = || generation.load(Ordering::Acquire) != request_generation;
+                let size = total_size(&path, cancelled);
+
+                if size_tx.send((path, size)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        self.dir_size.request_sender = Some(request_tx);
+
+        size_rx
+    }
+
+    /// Compute sizes of marked directories, or the selected one when nothing is marked.
+    pub fn compute_dir_size(&mut self) -> AppResult<()> {
+        let dirs: Vec<PathBuf> = match self.marked_files.get(&self.path) {
+            Some(marked) if !marked.files.is_empty() => marked.files.iter()
+                .filter(|(_, is_dir)| **is_dir)
+                .map(|(name, _)| self.path.join(name))
+                .collect(),
+
+            _ => match self.get_file_saver() {
+                Some(file) if file.is_dir => vec![self.path.join(&file.name)],
+                Some(_) => rt_error!("The selected file is not a directory"),
+                None => return Err(ErrorType::NoSelected.pack())
+            }
+        };
+
+        for dir in dirs.into_iter() {
+            self.dir_size.request(dir, true);
+        }
+
+        Ok(())
+    }
+
+    pub fn toggle_disk_usage(&mut self) -> AppResult<()> {
+        self.dir_size.usage_mode = !self.dir_size.usage_mode;
+        self.update_with_prev_selected(None)
+    }
+
+    /// Compute sizes of directories in current directory for disk usage mode,
+    /// computations out of current directory are cancelled.
+    pub fn request_disk_usage(&mut self) {
+        let path = self.current_path();
+        self.dir_size.set_scope(path.to_owned());
+
+        if !self.dir_size.usage_mode {
+            return
+        }
+
+        for file in self.current_files.iter() {
+            if file.is_dir {
+                self.dir_size.request(path.join(&file.name), false);
+            }
+        }
+    }
+
+    /// Cache the computed sizes, and sort current directory again in disk usage mode.
+    pub fn handle_dir_size<I>(&mut self, responses: I)
+    where I: Iterator<Item = SizeResponse>
+    {
+        let current_path = self.current_path();
+        let mut resort = false;
+
+        for (path, size) in responses {
+            self.dir_size.pending.remove(&path);
+
+            // The cancelled one will be requested again when it's needed.
+            if let Some(size) = size {
+                resort |= path.parent() == Some(current_path.as_path());
+                self.dir_size.cache.insert(path, size);
+            }
+        }
+
+        if !resort || !self.dir_size.usage_mode {
+            return
+        }
+
+        // Keep the selected file after sorting.
+        let selected = self.selected_item.current_selected()
+            .and_then(|idx| self.current_files.get(idx))
+            .map(|file| file.name.to_owned());
+
+        self.dir_size.sort(&current_path, &mut self.current_files);
+
+        if let Some(name) = selected {
+            let idx = self.current_files.iter().position(|file| file.name == name);
+            self.selected_item.current_select(idx);
+        }
+    }
+}
+
+/// Sum sizes of files under PATH, symlinks inside it aren't followed.
+/// Return None once CANCELLED is true.
+fn total_size<F>(path: &Path, cancelled: F) -> Option<u64>
+where F: Fn() -> bool
+{
+    let mut total = 0;
+    let mut dirs = vec![path.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        if cancelled() {
+            return None
+        }
+
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue
+        };
+
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue
+            };
+
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                total += metadata.len();
+            }
+        }
+    }
+
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_dir_size() {
+        let root = TestDir::new("dir_size");
+        fs::create_dir_all(root.join("small")).unwrap();
+        fs::create_dir_all(root.join("large/inner")).unwrap();
+        fs::write(root.join("small/a"), [0; 10]).unwrap();
+        fs::write(root.join("large/b"), [0; 100]).unwrap();
+        fs::write(root.join("large/inner/c"), [0; 1000]).unwrap();
+        fs::write(root.join("file"), [0; 50]).unwrap();
+
+        assert_eq!(total_size(&root.join("large"), || false), Some(1100));
+        assert_eq!(total_size(&root, || false), Some(1160));
+        assert_eq!(total_size(&root, || true), None);
+
+        let mut dir_size = DirSize::default();
+        dir_size.cache.insert(root.join("small"), 10);
+        dir_size.cache.insert(root.join("large"), 1100);
+
+        let mut files: Vec<FileSaver> = ["small", "file", "large"].into_iter()
+            .map(|name| FileSaver::new(name, root.join(name), None))
+            .collect();
+        dir_size.sort(&root, &mut files);
+
+        let names: Vec<&str> = files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(names, ["large", "file", "small"]);
+    }
+}
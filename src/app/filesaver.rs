@@ -1,7 +1,8 @@
 // FileSaver
 
 use std::io;
-use std::path::PathBuf;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use std::fs::{self, Permissions};
 
@@ -11,6 +12,28 @@ use ratatui::{
     text::Span,
 };
 
+/// The property used to sort files.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    /// Byte-wise order of file names.
+    #[default]
+    Name,
+    IgnoreCase,
+
+    /// Version order, e.g. "file2" < "file10".
+    Natural,
+    Size,
+    Modified,
+    Extension,
+}
+
+/// The way to sort files, directories always come first.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct SortMode {
+    pub key: SortKey,
+    pub reverse: bool,
+}
+
 /// The structure used to save file information.
 #[derive(Clone)]
 pub struct FileSaver {
@@ -22,9 +45,15 @@ pub struct FileSaver {
     pub dangling_symlink: bool,
     pub symlink_file: Option<PathBuf>,
 
+    /// The number of entries in directory, only counted for `count` column.
+    pub item_count: Option<usize>,
+
     size: u64,
     permissions: Option<Permissions>,
     modified_time: Option<SystemTime>,
+
+    /// Format: (uid, gid)
+    owner: Option<(u32, u32)>,
 }
 
 impl Default for FileSaver {
@@ -39,7 +68,9 @@ impl Default for FileSaver {
             executable: false,
             permissions: None,
             modified_time: None,
-            symlink_file: None
+            symlink_file: None,
+            item_count: None,
+            owner: None,
         }
     }
 }
@@ -53,6 +84,8 @@ impl FileSaver {
     where S: Into<String>,
           P: AsRef<std::path::Path>
     {
+        use std::os::unix::fs::MetadataExt;
+
         let meta = if let
             Some(metadata) = meta
         {
@@ -99,12 +132,36 @@ impl FileSaver {
                         metadata.modified()
                             .expect("Cannot get last modified time!")
                     ),
-                    permissions: Some(metadata.permissions())
+                    permissions: Some(metadata.permissions()),
+                    owner: Some((metadata.uid(), metadata.gid())),
+                    item_count: None,
                 }
             }
         }
     }
 
+    /// Create the file saver of an entry inside archive, which doesn't exist in file system.
+    pub fn archive_entry<S: Into<String>>(name: S,
+                                          is_dir: bool,
+                                          size: u64,
+                                          mode: u32,
+                                          modified_time: Option<SystemTime>
+    ) -> Self
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        FileSaver {
+            name: name.into(),
+            size,
+            is_dir,
+            is_file: !is_dir,
+            executable: !is_dir && mode & 0o111 != 0,
+            permissions: Some(Permissions::from_mode(mode)),
+            modified_time,
+            ..Default::default()
+        }
+    }
+
     fn dangling_symlink<T: Into<String>>(self, name: T) -> Self {
         let mut temp = self;
         temp.name = name.into();
@@ -119,13 +176,33 @@ impl FileSaver {
         temp
     }
 
-    /// Get permission span of file.
-    pub fn permission_span<'a>(&self) -> Span<'a> {
-        if self.read_only() {
-            Span::raw("READONLY").red().bold()
-        } else {
-            Span::raw("MUTABLE").light_green().bold()
+    /// Get permissions like `drwxr-xr-x` with owner & group of file.
+    pub fn permission_spans<'a>(&self) -> Vec<Span<'a>> {
+        let mut spans: Vec<Span> = self.mode_text()
+            .chars()
+            .map(|c| {
+                let span = Span::raw(c.to_string());
+                match c {
+                    'd' => span.blue().bold(),
+                    'l' => span.cyan().bold(),
+                    'r' => span.yellow(),
+                    'w' => span.red(),
+                    'x' => span.green(),
+                    's' | 'S' | 't' | 'T' => span.magenta(),
+                    _ => span.dark_gray(),
+                }
+            })
+            .collect();
+
+        if let Some((uid, gid)) = self.owner {
+            spans.push(Span::raw(format!(
+                " {} {}",
+                crate::utils::user_name(uid),
+                crate::utils::group_name(gid)
+            )));
         }
+
+        spans
     }
 
     pub fn read_only(&self) -> bool {
@@ -145,16 +222,68 @@ impl FileSaver {
     }
 
     pub fn modified_span<'a>(&self) -> Span<'a> {
+        Span::raw(self.modified_text("%Y-%m-%d %H:%M"))
+    }
+
+    /// Format last modified time with FORMAT of strftime.
+    pub fn modified_text(&self, format: &str) -> String {
         use chrono::{DateTime, Local};
 
         if let Some(time) = self.modified_time {
             let datetime: DateTime<Local> = time.into();
-            Span::raw(datetime.format("%Y-%m-%d %H:%M").to_string())
+            datetime.format(format).to_string()
         } else {
-            Span::raw("")
+            String::new()
         }
     }
 
+    /// Get permission bits of file, including setuid, setgid & sticky bits.
+    pub fn mode(&self) -> Option<u32> {
+        use std::os::unix::fs::PermissionsExt;
+
+        self.permissions.as_ref().map(|permissions| permissions.mode() & 0o7777)
+    }
+
+    /// Get permissions like `drwxr-xr-x`.
+    pub fn mode_text(&self) -> String {
+        let Some(mode) = self.mode() else {
+            return String::new()
+        };
+
+        let mut text = String::from(if self.symlink_file.is_some() {
+            'l'
+        } else if self.is_dir {
+            'd'
+        } else {
+            '-'
+        });
+
+        // Format: (shift of rwx bits, special bit, char with execute bit, char without it)
+        let classes = [(6, 0o4000, 's', 'S'), (3, 0o2000, 's', 'S'), (0, 0o1000, 't', 'T')];
+        for (shift, special, exec_char, plain_char) in classes {
+            let bits = mode >> shift;
+            text.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+            text.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+            text.push(match (bits & 0o1 != 0, mode & special != 0) {
+                (true, true)   => exec_char,
+                (false, true)  => plain_char,
+                (true, false)  => 'x',
+                (false, false) => '-',
+            });
+        }
+
+        text
+    }
+
+    /// Format: (uid, gid)
+    pub fn owner(&self) -> Option<(u32, u32)> {
+        self.owner
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
     pub fn size_span<'a>(&self) -> Span<'a> {
         Span::raw(file_size::fit_4(self.size))
     }
@@ -171,7 +300,73 @@ impl FileSaver {
 
 }
 
-pub fn sort(files: &mut Vec<FileSaver>) {
+impl SortKey {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortKey::Name       => "name",
+            SortKey::IgnoreCase => "ignore_case",
+            SortKey::Natural    => "natural",
+            SortKey::Size       => "size",
+            SortKey::Modified   => "modified",
+            SortKey::Extension  => "extension",
+        }
+    }
+}
+
+impl SortMode {
+    /// Parse sort mode from string like "size" or "modified reverse".
+    pub fn from_str(value: &str) -> anyhow::Result<Self> {
+        let mut slices = value.split_whitespace();
+        let key = match slices.next() {
+            Some("name")        => SortKey::Name,
+            Some("ignore_case") => SortKey::IgnoreCase,
+            Some("natural")     => SortKey::Natural,
+            Some("size")        => SortKey::Size,
+            Some("modified")    => SortKey::Modified,
+            Some("extension")   => SortKey::Extension,
+            _ => anyhow::bail!("Unknow sort mode: {}", value)
+        };
+
+        let reverse = match slices.next() {
+            None => false,
+            Some("reverse") => true,
+            Some(_) => anyhow::bail!("Unknow sort mode: {}", value)
+        };
+
+        Ok(Self { key, reverse })
+    }
+
+    fn compare(&self, a: &FileSaver, b: &FileSaver) -> Ordering {
+        let ordering = match self.key {
+            SortKey::Name       => Ordering::Equal,
+            SortKey::Natural    => natural_cmp(&a.name, &b.name),
+            SortKey::Size       => a.size.cmp(&b.size),
+            SortKey::Modified   => a.modified_time.cmp(&b.modified_time),
+            SortKey::IgnoreCase => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortKey::Extension  => {
+                Path::new(&a.name).extension().cmp(&Path::new(&b.name).extension())
+            },
+        }.then_with(|| a.name.cmp(&b.name));
+
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+impl std::fmt::Display for SortMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.reverse {
+            write!(f, "{} reverse", self.key.name())
+        } else {
+            write!(f, "{}", self.key.name())
+        }
+    }
+}
+
+pub fn sort(files: &mut Vec<FileSaver>, mode: SortMode) {
     if files.is_empty() {
         return ()
     }
@@ -185,9 +380,84 @@ pub fn sort(files: &mut Vec<FileSaver>) {
             normal_files.push((*file).clone());
         }
     }
-    directories.sort_by(|a, b| a.name.cmp(&b.name));
-    normal_files.sort_by(|a, b| a.name.cmp(&b.name));
+    directories.sort_by(|a, b| mode.compare(a, b));
+    normal_files.sort_by(|a, b| mode.compare(a, b));
     directories.extend(normal_files);
 
     *files = directories;
 }
+
+/// Compare names with the numbers inside them as integers.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut a_num = String::new();
+                while let Some(c) = a_chars.next_if(|c| c.is_ascii_digit()) {
+                    a_num.push(c);
+                }
+
+                let mut b_num = String::new();
+                while let Some(c) = b_chars.next_if(|c| c.is_ascii_digit()) {
+                    b_num.push(c);
+                }
+
+                let a_num = a_num.trim_start_matches('0');
+                let b_num = b_num.trim_start_matches('0');
+                let ordering = a_num.len().cmp(&b_num.len()).then(a_num.cmp(b_num));
+
+                if ordering != Ordering::Equal {
+                    return ordering
+                }
+            },
+
+            (Some(x), Some(y)) => {
+                let ordering = x.cmp(y);
+                if ordering != Ordering::Equal {
+                    return ordering
+                }
+
+                a_chars.next();
+                b_chars.next();
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp() {
+        assert_eq!(natural_cmp("file2", "file10"), Ordering::Less);
+        assert_eq!(natural_cmp("v1.10.0", "v1.9.2"), Ordering::Greater);
+        assert_eq!(natural_cmp("a007", "a7"), Ordering::Equal);
+        assert_eq!(natural_cmp("abc", "abcd"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_sort_mode() {
+        let mode = SortMode::from_str("modified reverse").unwrap();
+        assert!(mode.key == SortKey::Modified && mode.reverse);
+        assert_eq!(mode.to_string(), "modified reverse");
+        assert!(SortMode::from_str("size up").is_err());
+    }
+
+    #[test]
+    fn test_mode_text() {
+        let file = FileSaver::archive_entry("bin", false, 0, 0o4755, None);
+        assert_eq!(file.mode(), Some(0o4755));
+        assert_eq!(file.mode_text(), "-rwsr-xr-x");
+
+        let dir = FileSaver::archive_entry("tmp", true, 0, 0o1776, None);
+        assert_eq!(dir.mode_text(), "drwxrwxrwT");
+    }
+}
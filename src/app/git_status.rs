@@ -0,0 +1,311 @@
+// Git Status
+
+use std::{
+    thread,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+use super::App;
+
+pub type GitResponse = (PathBuf, Option<GitRepo>);
+
+/// The state of a file in git repository.
+/// Variants are ordered by priority, which decides the state shown for a directory.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GitState {
+    Ignored,
+    Untracked,
+    Staged,
+    Modified,
+    Conflicted,
+}
+
+/// The status of the repository containing current directory.
+pub struct GitRepo {
+    /// The root as the path requested, which may contain symlinks unlike the one git shows.
+    root: PathBuf,
+    branch: String,
+
+    /// Format: (ahead, behind), which is None without upstream.
+    ahead_behind: Option<(usize, usize)>,
+
+    /// Paths relative to root, untracked & ignored directories are included.
+    files: HashMap<PathBuf, GitState>,
+
+    /// The directories containing changed files.
+    dirs: HashMap<PathBuf, GitState>,
+}
+
+/// Compute git status in background when entering a directory.
+#[derive(Default)]
+pub struct GitStatus {
+    repo: Option<GitRepo>,
+
+    /// The directory whose status is requested last.
+    requested: Option<PathBuf>,
+    request_sender: Option<Sender<PathBuf>>,
+}
+
+impl GitState {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            GitState::Ignored    => "!",
+            GitState::Untracked  => "?",
+            GitState::Staged     => "+",
+            GitState::Modified   => "M",
+            GitState::Conflicted => "U",
+        }
+    }
+}
+
+impl GitRepo {
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    pub fn ahead_behind(&self) -> Option<(usize, usize)> {
+        self.ahead_behind
+    }
+
+    /// Get the state of file at PATH, a directory has the state of its changed files.
+    pub fn state(&self, path: &Path) -> Option<GitState> {
+        let path = path.strip_prefix(&self.root).ok()?;
+        if let Some(state) = self.files.get(path).or_else(|| self.dirs.get(path)) {
+            return Some(*state)
+        }
+
+        // Files inside untracked or ignored directories.
+        path.ancestors()
+            .skip(1)
+            .find_map(|dir| self.files.get(dir))
+            .filter(|state| **state <= GitState::Untracked)
+            .copied()
+    }
+
+    /// Parse output of `git status --porcelain=v2 --branch -z`.
+    fn parse(root: PathBuf, output: &str) -> Self {
+        let mut repo = GitRepo {
+            root,
+            branch: String::new(),
+            ahead_behind: None,
+            files: HashMap::new(),
+            dirs: HashMap::new(),
+        };
+
+        let mut entries = output.split('\0');
+        while let Some(entry) = entries.next() {
+            if let Some(header) = entry.strip_prefix("# ") {
+                repo.parse_header(header);
+                continue;
+            }
+
+            // Format: <type> <XY> ... <path>, the number of fields differs among types.
+            let (state, fields) = match entry.split_at_checked(2) {
+                Some(("1 ", rest)) => (change_state(rest), 8),
+                Some(("u ", _)) => (GitState::Conflicted, 10),
+                Some(("? ", _)) => (GitState::Untracked, 1),
+                Some(("! ", _)) => (GitState::Ignored, 1),
+                Some(("2 ", rest)) => {
+                    // The original path of renamed file is the next entry.
+                    entries.next();
+                    (change_state(rest), 9)
+                },
+                _ => continue
+            };
+
+            let Some(path) = entry.splitn(fields + 1, ' ').last() else {
+                continue
+            };
+            repo.add_file(PathBuf::from(path.trim_end_matches('/')), state);
+        }
+
+        repo
+    }
+
+    fn parse_header(&mut self, header: &str) {
+        if let Some(branch) = header.strip_prefix("branch.head ") {
+            self.branch = branch.to_owned();
+        } else if let Some(counts) = header.strip_prefix("branch.ab ") {
+            let mut counts = counts.split(' ')
+                .map(|count| count.trim_start_matches(['+', '-']).parse::<usize>().unwrap_or(0));
+
+            self.ahead_behind = Some((
+                counts.next().unwrap_or(0),
+                counts.next().unwrap_or(0)
+            ));
+        }
+    }
+
+    fn add_file(&mut self, path: PathBuf, state: GitState) {
+        // Ignored files don't make directories dirty.
+        if state != GitState::Ignored {
+            for dir in path.ancestors().skip(1) {
+                if dir.as_os_str().is_empty() {
+                    break;
+                }
+
+                let dir_state = self.dirs.entry(dir.to_path_buf()).or_insert(state);
+                *dir_state = (*dir_state).max(state);
+            }
+        }
+
+        self.files.insert(path, state);
+    }
+}
+
+/// Get the state from XY of a changed entry, the worktree changes take precedence.
+fn change_state(fields: &str) -> GitState {
+    let mut xy = fields.chars();
+    let staged = xy.next().is_some_and(|x| x != '.');
+    let modified = xy.next().is_some_and(|y| y != '.');
+
+    if modified || !staged {
+        GitState::Modified
+    } else {
+        GitState::Staged
+    }
+}
+
+impl GitStatus {
+    pub fn repo(&self) -> Option<&GitRepo> {
+        self.repo.as_ref()
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn init_git_status(&mut self) -> Receiver<GitResponse> {
+        let (request_tx, request_rx) = mpsc::channel::<PathBuf>();
+        let (status_tx, status_rx)   = mpsc::channel::<GitResponse>();
+
+        thread::spawn(move || {
+            while let Ok(request) = request_rx.recv() {
+                // Only compute the status of the latest directory.
+                let path = request_rx.try_iter().last().unwrap_or(request);
+                let repo = read_git_status(&path);
+
+                if status_tx.send((path, repo)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        self.git_status.request_sender = Some(request_tx);
+
+        status_rx
+    }
+
+    /// Compute git status of current directory when it's changed, or FORCE to refresh it.
+    pub fn request_git_status(&mut self, force: bool) {
+        let path = self.current_path();
+        if !force && self.git_status.requested.as_ref() == Some(&path) {
+            return
+        }
+
+        let sent = self.git_status.request_sender.as_ref()
+            .is_some_and(|sender| sender.send(path.to_owned()).is_ok());
+        if sent {
+            self.git_status.requested = Some(path);
+        }
+    }
+
+    /// Update git status when PATH is still the requested directory.
+    pub fn handle_git_status(&mut self, path: PathBuf, repo: Option<GitRepo>) {
+        if self.git_status.requested.as_ref() == Some(&path) {
+            self.git_status.repo = repo;
+        }
+    }
+}
+
+/// Get the status of repository containing PATH, None if it's not in a repository.
+fn read_git_status(path: &Path) -> Option<GitRepo> {
+    let output = run_git(path, &["rev-parse", "--show-toplevel", "--show-prefix"])?;
+    let mut lines = output.lines();
+    let toplevel = PathBuf::from(lines.next()?);
+
+    // The toplevel from git is resolved, so the root is found from PATH with the prefix,
+    // otherwise files in a directory reached by symlink don't match the root.
+    let mut root = path.to_path_buf();
+    for _ in Path::new(lines.next().unwrap_or_default()).components() {
+        root.pop();
+    }
+
+    let output = run_git(&toplevel, &["status", "--porcelain=v2", "--branch", "--ignored", "-z"])?;
+
+    Some(GitRepo::parse(root, &output))
+}
+
+fn run_git(path: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None
+    }
+
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_git_status() {
+        let output = [
+            "# branch.oid 0123456789abcdef",
+            "# branch.head main",
+            "# branch.upstream origin/main",
+            "# branch.ab +2 -1",
+            "1 .M N... 100644 100644 100644 0123 0123 src/main.rs",
+            "1 A. N... 000000 100644 100644 0000 0123 src/app/new file.rs",
+            "2 R. N... 100644 100644 100644 0123 0123 R100 src/app/renamed.rs",
+            "src/app/old.rs",
+            "u UU N... 100644 100644 100644 100644 0123 0123 0123 README.md",
+            "? notes/",
+            "! target/",
+            "",
+        ].join("\0");
+
+        let repo = GitRepo::parse(PathBuf::from("/repo"), &output);
+        assert_eq!(repo.branch(), "main");
+        assert_eq!(repo.ahead_behind(), Some((2, 1)));
+
+        let state = |path: &str| repo.state(&Path::new("/repo").join(path));
+        assert!(state("src/main.rs") == Some(GitState::Modified));
+        assert!(state("src/app/new file.rs") == Some(GitState::Staged));
+        assert!(state("src/app/renamed.rs") == Some(GitState::Staged));
+        assert!(state("src/app/old.rs").is_none());
+        assert!(state("README.md") == Some(GitState::Conflicted));
+        assert!(state("notes/todo.md") == Some(GitState::Untracked));
+        assert!(state("target/debug") == Some(GitState::Ignored));
+        assert!(state("Cargo.toml").is_none());
+
+        // Dirty directories
+        assert!(state("src") == Some(GitState::Modified));
+        assert!(state("src/app") == Some(GitState::Staged));
+    }
+
+    #[test]
+    fn test_symlinked_repo() {
+        let root = TestDir::new("git_status");
+        std::fs::create_dir_all(root.join("repo/src")).unwrap();
+        std::fs::write(root.join("repo/src/main.rs"), "").unwrap();
+        std::os::unix::fs::symlink(root.join("repo"), root.join("link")).unwrap();
+
+        if run_git(&root.join("repo"), &["init", "-q"]).is_some() {
+            let repo = read_git_status(&root.join("link/src")).unwrap();
+            assert_eq!(repo.root, root.join("link"));
+            assert!(repo.state(&root.join("link/src/main.rs")) == Some(GitState::Untracked));
+        }
+    }
+}
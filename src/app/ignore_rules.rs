@@ -0,0 +1,107 @@
+// Ignore Rules
+
+use std::{
+    borrow::Cow,
+    path::Path,
+};
+
+use anyhow::Result;
+use ignore::{Match, gitignore::{Gitignore, GitignoreBuilder}};
+
+/// Hide files matched by `.gitignore`, `.ignore` and `ignore_patterns` in user_config.toml.
+#[derive(Default)]
+pub struct IgnoreRules {
+    /// Patterns from user config, which match files in any directory.
+    user: Option<Gitignore>,
+}
+
+/// The rules applied to files in a directory.
+pub struct IgnoreMatcher<'a> {
+    /// Ignore files of the directory & its ancestors, the nearer one takes precedence.
+    files: Vec<Gitignore>,
+    user: Option<&'a Gitignore>,
+}
+
+impl IgnoreRules {
+    /// Set user-level PATTERNS, written in the syntax of gitignore.
+    pub fn set_patterns(&mut self, patterns: &[Cow<str>]) -> Result<()> {
+        if patterns.is_empty() {
+            return Ok(())
+        }
+
+        let mut builder = GitignoreBuilder::new("/");
+        for pattern in patterns.iter() {
+            builder.add_line(None, pattern)?;
+        }
+        self.user = Some(builder.build()?);
+
+        Ok(())
+    }
+
+    /// Read ignore files for files in DIR.
+    /// `.gitignore` only works in git repository, while `.ignore` works anywhere.
+    pub fn matcher(&self, dir: &Path) -> IgnoreMatcher<'_> {
+        let repo = dir.ancestors().find(|path| path.join(".git").exists());
+
+        let mut files = Vec::new();
+        for path in dir.ancestors() {
+            let in_repo = repo.is_some_and(|root| path.starts_with(root));
+            let names = if in_repo {
+                &[".ignore", ".gitignore"][..]
+            } else {
+                &[".ignore"][..]
+            };
+
+            for name in names {
+                let file = path.join(name);
+                if file.is_file() {
+                    files.push(Gitignore::new(file).0);
+                }
+            }
+        }
+
+        IgnoreMatcher { files, user: self.user.as_ref() }
+    }
+}
+
+impl IgnoreMatcher<'_> {
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        for gitignore in self.files.iter() {
+            match gitignore.matched(path, is_dir) {
+                Match::None => continue,
+                matched => return matched.is_ignore()
+            }
+        }
+
+        self.user.is_some_and(|user| user.matched(path, is_dir).is_ignore())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_ignore_rules() {
+        let root = TestDir::new("ignore");
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(root.join("src/.ignore"), "!keep.log\n").unwrap();
+
+        let mut rules = IgnoreRules::default();
+        rules.set_patterns(&[Cow::Borrowed("node_modules/")]).unwrap();
+
+        let matcher = rules.matcher(&root);
+        assert!(matcher.is_ignored(&root.join("debug.log"), false));
+        assert!(matcher.is_ignored(&root.join("node_modules"), true));
+        assert!(!matcher.is_ignored(&root.join("node_modules"), false));
+        assert!(!matcher.is_ignored(&root.join("src"), true));
+
+        // The nearer ignore file takes precedence.
+        let matcher = rules.matcher(&root.join("src"));
+        assert!(matcher.is_ignored(&root.join("src/debug.log"), false));
+        assert!(!matcher.is_ignored(&root.join("src/keep.log"), false));
+    }
+}
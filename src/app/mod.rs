@@ -1,24 +1,53 @@
 // App
 
 mod color;
+mod dir_size;
 mod filesaver;
+mod git_status;
+mod ignore_rules;
 mod image_preview;
+mod previewer;
 
 use std::{env, fs, io};
 
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::{PathBuf, Path};
+use std::time::SystemTime;
 
 use ratatui::text::Text;
 use image_preview::ImagePreview;
+use previewer::Previewer;
+use git_status::GitStatus;
+use ignore_rules::{IgnoreMatcher, IgnoreRules};
 use ratatui::widgets::ListState;
 
-use crate::config::{AppConfig, Keymap};
+use crate::command::CommandHistory;
+use crate::config::{AppConfig, Config, ConfigValue, Keymap, OpenRule};
 use crate::error::{AppError, AppResult};
-use crate::key_event::{AppCompletion, EditMode, FileSearcher, NaviIndex, SwitchCase};
+use crate::key_event::{
+    AppCompletion,
+    FileSearcher,
+    FileFilter,
+    Finder,
+    GrepSearch,
+    SwitchCase,
+    NaviIndex,
+    EditMode,
+    JobManager,
+    DirWatcher,
+    SortConfig,
+    ArchiveCache,
+    Journal,
+    PendingKeys,
+    MacroRecorder,
+    HistorySearch,
+    archive_entry_path,
+    split_archive_path,
+};
 use crate::utils::{
     read_to_text,
+    HIGHLIGHT_SIZE_LIMIT,
     MarkedFiles,
     FileContent,
     SearchFile,
@@ -27,13 +56,19 @@ use crate::utils::{
 };
 
 pub use color::TermColors;
-pub use filesaver::{sort, FileSaver};
+pub use filesaver::{sort, FileSaver, SortKey, SortMode};
+pub use git_status::{GitRepo, GitState};
+pub use dir_size::DirSize;
 
 pub struct App<'a> {
     // Core
     pub path: PathBuf,
     pub quit_now: bool,
     pub hide_files: bool,
+
+    /// Whether to hide files matched by ignore files & `ignore_patterns`.
+    pub ignore_files: bool,
+    pub ignore_rules: IgnoreRules,
     pub selected_item: ItemIndex,
     pub child_files: Vec<FileSaver>,
     pub parent_files: Vec<FileSaver>,
@@ -57,12 +92,16 @@ pub struct App<'a> {
     pub command_scroll: Option<(u16, u16)>, // Used for expanded mode.
 
     pub command_idx: Option<usize>,
-    pub command_history: Vec<String>,
+    pub command_history: CommandHistory,
+    pub history_search: Option<HistorySearch>,
 
     pub command_completion: AppCompletion<'a>,
 
     // Search file
     pub file_searcher: FileSearcher,
+    pub file_filter: Option<FileFilter>,
+    pub finder: Option<Finder>,
+    pub grep: Option<GrepSearch>,
 
     // ColorScheme
     pub term_colors: TermColors,
@@ -76,12 +115,46 @@ pub struct App<'a> {
     // Image Preview
     pub image_preview: ImagePreview,
 
+    // External Previewer
+    pub previewer: Previewer,
+
+    // Git Status
+    pub git_status: GitStatus,
+
+    // Directory Size
+    pub dir_size: DirSize,
+
+    /// The file with the number of lines skipped in preview, used to show a result of grep.
+    pub preview_line: Option<(PathBuf, usize)>,
+
     // Edit Mode
     pub edit_mode: EditMode,
 
     /// When this var is true, the navigation will be with selection.
     pub mark_expand: bool,
 
+    // Undo & Redo
+    pub journal: Journal,
+
+    // Background Jobs
+    pub jobs: JobManager,
+
+    // Sort Modes
+    pub sort_config: SortConfig,
+
+    // Directory Watcher
+    pub dir_watcher: DirWatcher,
+
+    // Archive Browsing
+    pub archive_cache: ArchiveCache,
+
+    /// The program chosen to open file, which is run with terminal later.
+    pub pending_open: Option<(OpenRule, PathBuf)>,
+
+    // Key Sequence
+    pub pending_keys: PendingKeys,
+    pub macros: MacroRecorder,
+
     // App Config
     pub keymap: Keymap,
     pub config_path: String,
@@ -126,6 +199,8 @@ impl<'a> Default for App<'a> {
             term_colors,
             selected_block,
             hide_files: true,
+            ignore_files: false,
+            ignore_rules: IgnoreRules::default(),
             file_content: FileContent::None,
             selected_item: ItemIndex::default(),
 
@@ -136,15 +211,31 @@ impl<'a> Default for App<'a> {
             command_scroll: None,
             target_dir: HashMap::new(),
             marked_files: HashMap::new(),
+            journal: Journal::default(),
+            jobs: JobManager::default(),
+            sort_config: SortConfig::default(),
+            dir_watcher: DirWatcher::default(),
+            archive_cache: ArchiveCache::default(),
+            pending_open: None,
+            pending_keys: PendingKeys::default(),
+            macros: MacroRecorder::default(),
             edit_mode: EditMode::default(),
             navi_index: NaviIndex::default(),
             image_preview: ImagePreview::default(),
+            previewer: Previewer::default(),
+            preview_line: None,
+            git_status: GitStatus::default(),
+            dir_size: DirSize::default(),
             file_searcher: FileSearcher::default(),
+            file_filter: None,
+            finder: None,
+            grep: None,
 
             // Command
             command_idx: None,
             command_expand: false,
-            command_history: Vec::new(),
+            command_history: CommandHistory::default(),
+            history_search: None,
             command_completion: AppCompletion::default(),
 
             // Error handle
@@ -312,7 +403,7 @@ impl<'a> App<'a> {
             let mut parent_files = self.read_files(temp_path.as_path())?;
 
             if temp_path.to_string_lossy() == "/" {
-                sort(&mut parent_files);
+                sort(&mut parent_files, self.sort_config.get(&temp_path));
                 self.parent_files = parent_files;
                 break;
             }
@@ -337,10 +428,10 @@ impl<'a> App<'a> {
                             )
                         );
 
-                        sort(&mut parent_files);
+                        sort(&mut parent_files, self.sort_config.get(&temp_path));
                     },
                     _ => {
-                        sort(&mut parent_files);
+                        sort(&mut parent_files, self.sort_config.get(&temp_path));
 
                         match parent_files.get(0) {
                             Some(file) if file.is_dir => {
@@ -354,7 +445,7 @@ impl<'a> App<'a> {
                     },
                 }
             } else {
-                sort(&mut parent_files);
+                sort(&mut parent_files, self.sort_config.get(&temp_path));
             }
 
 
@@ -379,7 +470,17 @@ impl<'a> App<'a> {
             return Ok(())
         }
 
-        sort(&mut current_files);
+        sort(&mut current_files, self.sort_config.get(&temp_path));
+        if self.count_items() {
+            for file in current_files.iter_mut().filter(|file| file.is_dir) {
+                file.item_count = fs::read_dir(temp_path.join(&file.name))
+                    .map(|dir| dir.count())
+                    .ok();
+            }
+        }
+        if self.dir_size.usage_mode() {
+            self.dir_size.sort(&temp_path, &mut current_files);
+        }
 
         self.current_files = current_files;
 
@@ -401,10 +502,11 @@ impl<'a> App<'a> {
         };
 
         if current_select.is_dir {
+            let child_path = temp_path.join(&current_select.name);
             let mut child_files: Vec<FileSaver> = self.read_files(
-                temp_path.join(&current_select.name).as_path()
+                child_path.as_path()
             )?;
-            sort(&mut child_files);
+            sort(&mut child_files, self.sort_config.get(&child_path));
 
             self.child_files = child_files;
             if self.file_content.is_some() {
@@ -529,6 +631,9 @@ impl<'a> App<'a> {
             None
         };
 
+        // Files may be changed, so is git status.
+        self.request_git_status(true);
+
         // The first one is for parent, the other one for current.
         let mut select_prev = [true, true];
         let root = self.root();
@@ -625,12 +730,18 @@ impl<'a> App<'a> {
         self.update_with_prev_selected(target)?;
         Ok(())
     }
+
+    pub fn ignore_or_show(&mut self) -> AppResult<()> {
+        self.ignore_files = !self.ignore_files;
+        self.update_with_prev_selected(None)?;
+        Ok(())
+    }
 }
 
 // File Content
 impl<'a> App<'a> {
     pub fn set_file_content(&mut self) -> anyhow::Result<()> {
-        use io::ErrorKind;
+        use io::{BufRead, ErrorKind};
 
         let selected = self.get_file_saver();
         if let Some(selected_item) = selected {
@@ -639,12 +750,39 @@ impl<'a> App<'a> {
                 .join(&selected_file.name);
             let mut content = Text::default();
 
+            // The skipped lines only work for the file they're set for.
+            let skipped_lines = match self.preview_line {
+                Some((ref path, lines)) if *path == file_path => lines,
+                Some(_) => {
+                    self.preview_line = None;
+                    0
+                },
+                None => 0
+            };
+
             // To avoid the wrong display of file content caused by
             // image decoding delay.
             if !self.image_preview.useless {
                 self.image_preview.useless = true;
             }
 
+            // Files inside archives are read from the archive in previewer thread,
+            // decompressing large archives takes a while.
+            if let Some((archive, _)) = archive_entry_path(&file_path) {
+                let mtime = fs::metadata(archive)
+                    .and_then(|metadata| metadata.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                if let Some(content) = self.previewer.cached(&file_path, mtime) {
+                    self.file_content = FileContent::Text(content);
+                    return Ok(())
+                }
+
+                self.previewer.send_archive_entry(file_path, mtime)?;
+                self.file_content = FileContent::Text(Text::raw("Loading..."));
+
+                return Ok(())
+            }
+
             match fs::File::open(file_path.to_owned()) {
                 Err(e) => {
                     match e.kind() {
@@ -657,7 +795,36 @@ impl<'a> App<'a> {
                 },
                 Ok(ref mut file) => {
                     if selected_file.is_file {
-                        if let Err(_) = read_to_text(&mut content, file) {
+                        // Files matched previewers are shown with output of external commands.
+                        if let Some(command) = self.previewer.find(&file_path) {
+                            let mtime = file.metadata()?.modified()?;
+                            if let Some(content) = self.previewer.cached(&file_path, mtime) {
+                                self.file_content = FileContent::Text(content);
+                                return Ok(())
+                            }
+
+                            self.previewer.send(file_path, mtime, command.to_owned())?;
+                            self.file_content = FileContent::Text(Text::raw("Loading..."));
+
+                            return Ok(())
+                        }
+
+                        // Large files are shown without highlighting to keep fast.
+                        let syntax = self.highlight_theme()
+                            .filter(|_| {
+                                file.metadata()
+                                    .is_ok_and(|metadata| metadata.len() <= HIGHLIGHT_SIZE_LIMIT)
+                            })
+                            .map(|theme| (file_path.as_path(), theme));
+
+                        let mut reader = io::BufReader::new(file);
+                        for _ in 0..skipped_lines {
+                            if reader.skip_until(b'\n')? == 0 {
+                                break;
+                            }
+                        }
+
+                        if let Err(_) = read_to_text(&mut content, reader, syntax) {
                             // if e.kind() != io::ErrorKind::InvalidData {
                             //     return Err(e.into())
                             // }
@@ -683,19 +850,57 @@ impl<'a> App<'a> {
         Ok(())
     }
 
-    fn read_files(&mut self, path: &Path) -> io::Result<Vec<FileSaver>> {
+    /// The theme used to highlight file content, None when it's disabled.
+    fn highlight_theme(&self) -> Option<&str> {
+        match Config::get_value(&self.config, "highlight_theme") {
+            ConfigValue::String(theme) if theme != "none" => Some(theme.as_ref()),
+            _ => None
+        }
+    }
+
+    /// Whether to count entries of directories for `count` column.
+    fn count_items(&self) -> bool {
+        match Config::get_value(&self.config, "columns") {
+            ConfigValue::Columns(columns) => columns.contains(&crate::config::Column::Count),
+            _ => false
+        }
+    }
+
+    /// Whether FILE in directory PATH is shown, with hidden files & filter considered.
+    fn file_visible(&self,
+                    path: &Path,
+                    file: &FileSaver,
+                    ignore: Option<&IgnoreMatcher>
+    ) -> bool
+    {
+        if self.hide_files && file.name.starts_with(".") {
+            return false
+        }
+
+        if ignore.is_some_and(|matcher| matcher.is_ignored(&path.join(&file.name), file.is_dir)) {
+            return false
+        }
+
+        self.file_filter.as_ref().is_none_or(|filter| filter.is_match(path, &file.name))
+    }
+
+    pub fn read_files(&mut self, path: &Path) -> io::Result<Vec<FileSaver>> {
+        // Archives are browsed as virtual directories.
+        if let Some((archive, inner)) = split_archive_path(path) {
+            let files = self.archive_cache.list(&archive, &inner)?;
+            return Ok(files.into_iter()
+                      .filter(|file| self.file_visible(path, file, None))
+                      .collect())
+        }
+
         let temp_dir = fs::read_dir(path);
+        let ignore = self.ignore_files.then(|| self.ignore_rules.matcher(path));
 
         match temp_dir {
             Ok(dir) => {
-                let result = dir.map(filesave_closure);
-                if self.hide_files {
-                    Ok(result
-                       .filter(|file| !file.name.starts_with("."))
-                       .collect())
-                } else {
-                    Ok(result.collect())
-                }
+                Ok(dir.map(filesave_closure)
+                   .filter(|file| self.file_visible(path, file, ignore.as_ref()))
+                   .collect())
             }
             Err(err) => {
                 if err.kind() == io::ErrorKind::PermissionDenied {
@@ -744,6 +949,8 @@ impl<'a> App<'a> {
             }
         }
 
+        self.request_git_status(true);
+
         Ok(())
     }
     
@@ -0,0 +1,234 @@
+// External Previewer
+
+use std::{
+    thread,
+    borrow::Cow,
+    collections::HashMap,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, Instant, SystemTime},
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+use anyhow::{bail, Result};
+use ratatui::text::Text;
+
+use super::App;
+use crate::key_event::{archive_entry_path, read_entry};
+use crate::utils::{read_to_text, FileContent, FilePattern, HIGHLIGHT_SIZE_LIMIT};
+
+/// The longest time to wait for a previewer command.
+const PREVIEW_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The maximum bytes of output read from a previewer command.
+const OUTPUT_LIMIT: u64 = 1024 * 1024;
+
+/// The maximum number of previews kept in cache.
+const CACHE_LIMIT: usize = 64;
+
+/// Format: (path, modified time, output)
+pub type PreviewResponse = (PathBuf, SystemTime, PreviewOutput);
+
+enum PreviewRequest {
+    /// Format: (path, modified time, previewer command)
+    Command(PathBuf, SystemTime, String),
+
+    /// A file inside archive, which is read by decompressing the archive.
+    /// The modified time is the archive's.
+    ArchiveEntry(PathBuf, SystemTime),
+}
+
+pub enum PreviewOutput {
+    Command(Result<String>),
+    ArchiveEntry(io::Result<Vec<u8>>),
+}
+
+/// Run external commands to preview files, according to `previewers` in user_config.toml.
+/// Files inside archives are also read here, avoid blocking with large archives.
+///
+/// The previews are cached with modified time of files, thus refreshing
+/// won't run the commands again unless the files are changed.
+#[derive(Default)]
+pub struct Previewer {
+    rules: Vec<(FilePattern, String)>,
+    request_sender: Option<Sender<PreviewRequest>>,
+    cache: HashMap<PathBuf, (SystemTime, Text<'static>)>,
+}
+
+impl Previewer {
+    /// Set previewer rules from pairs of pattern & command.
+    pub fn set_rules(&mut self, rules: &[(Cow<str>, Cow<str>)]) -> Result<()> {
+        for (pattern, command) in rules.iter() {
+            self.rules.push((FilePattern::new(pattern)?, command.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Get the command to preview PATH, the first matched rule is used.
+    pub fn find(&self, path: &Path) -> Option<&str> {
+        self.rules.iter()
+            .find(|(pattern, _)| pattern.is_match(path))
+            .map(|(_, command)| command.as_str())
+    }
+
+    pub fn send(&self, path: PathBuf, mtime: SystemTime, command: String) -> Result<()> {
+        self.send_request(PreviewRequest::Command(path, mtime, command))
+    }
+
+    pub fn send_archive_entry(&self, path: PathBuf, mtime: SystemTime) -> Result<()> {
+        self.send_request(PreviewRequest::ArchiveEntry(path, mtime))
+    }
+
+    /// Get the cached preview of PATH, which is None when PATH has been modified.
+    pub fn cached(&self, path: &Path, mtime: SystemTime) -> Option<Text<'static>> {
+        self.cache.get(path)
+            .filter(|(time, _)| *time == mtime)
+            .map(|(_, content)| content.to_owned())
+    }
+
+    fn cache(&mut self, path: PathBuf, mtime: SystemTime, content: Text<'static>) {
+        if self.cache.len() >= CACHE_LIMIT && !self.cache.contains_key(&path) {
+            self.cache.clear();
+        }
+
+        self.cache.insert(path, (mtime, content));
+    }
+
+    fn send_request(&self, request: PreviewRequest) -> Result<()> {
+        if let Some(ref sender) = self.request_sender {
+            sender.send(request)?;
+            return Ok(())
+        }
+
+        bail!("Unable to init previewer thread")
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn init_previewer(&mut self) -> Receiver<PreviewResponse> {
+        let (request_tx, request_rx) = mpsc::channel::<PreviewRequest>();
+        let (output_tx, output_rx)   = mpsc::channel::<PreviewResponse>();
+
+        thread::spawn(move || {
+            while let Ok(request) = request_rx.recv() {
+                // Only preview the latest selected file.
+                let response = match request_rx.try_iter().last().unwrap_or(request) {
+                    PreviewRequest::Command(path, mtime, command) => {
+                        let output = run_previewer(&path, &command);
+                        (path, mtime, PreviewOutput::Command(output))
+                    },
+
+                    PreviewRequest::ArchiveEntry(path, mtime) => {
+                        let output = match archive_entry_path(&path) {
+                            Some((archive, inner)) => read_entry(&archive, &inner, HIGHLIGHT_SIZE_LIMIT),
+                            None => Err(io::Error::from(io::ErrorKind::NotFound))
+                        };
+                        (path, mtime, PreviewOutput::ArchiveEntry(output))
+                    },
+                };
+
+                if output_tx.send(response).is_err() {
+                    break;
+                }
+            }
+        });
+
+        self.previewer.request_sender = Some(request_tx);
+
+        output_rx
+    }
+
+    /// Cache output of previewer, and show it when PATH is still the selected file.
+    /// The failed previews are not cached, which are tried again when refreshing.
+    pub fn handle_preview_output(&mut self, path: PathBuf, mtime: SystemTime, output: PreviewOutput) {
+        let mut content = Text::default();
+        let result = match output {
+            PreviewOutput::Command(output) => {
+                let result = output
+                    .and_then(|output| read_to_text(&mut content, output.as_bytes(), None));
+                if let Err(ref err) = result {
+                    content = Text::raw(format!("Previewer Error: {}", err));
+                }
+                result
+            },
+
+            PreviewOutput::ArchiveEntry(bytes) => {
+                let syntax = self.highlight_theme()
+                    .map(|theme| (path.as_path(), theme));
+                let result = bytes
+                    .map_err(anyhow::Error::from)
+                    .and_then(|bytes| read_to_text(&mut content, bytes.as_slice(), syntax));
+                if result.is_err() {
+                    content = Text::raw("Non Text File");
+                }
+                result
+            },
+        };
+
+        if result.is_ok() {
+            self.previewer.cache(path.to_owned(), mtime, content.to_owned());
+        }
+
+        let is_selected = self.get_file_saver()
+            .is_some_and(|file| file.is_file && self.current_path().join(&file.name) == path);
+        if is_selected {
+            self.file_content = FileContent::Text(content);
+        }
+    }
+}
+
+/// Run COMMAND for PATH and get its output.
+/// The `{}` in COMMAND is replaced with PATH, otherwise PATH is appended as the last argument.
+fn run_previewer(path: &Path, command: &str) -> Result<String> {
+    let path_str = path.to_string_lossy();
+    let mut args: Vec<String> = command.split_whitespace()
+        .map(|arg| arg.replace("{}", &path_str))
+        .collect();
+
+    if !command.contains("{}") {
+        args.push(path_str.to_string());
+    }
+
+    if args.len() < 2 {
+        bail!("The previewer command for {} is empty", path_str)
+    }
+
+    let mut child = Command::new(args.remove(0))
+        .args(args)
+        .current_dir(path.parent().unwrap_or(Path::new("/")))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // Read output in another thread, avoid blocking the previewer with full pipe.
+    let stdout = child.stdout.take();
+    let reader = thread::spawn(move || {
+        let mut output: Vec<u8> = Vec::new();
+        if let Some(stdout) = stdout {
+            let _ = stdout.take(OUTPUT_LIMIT).read_to_end(&mut output);
+        }
+
+        output
+    });
+
+    let start_time = Instant::now();
+    while child.try_wait()?.is_none() {
+        if start_time.elapsed() > PREVIEW_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("The previewer timed out")
+        }
+
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let output = match reader.join() {
+        Ok(output) => output,
+        Err(_) => bail!("Failed to read output of previewer"),
+    };
+
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
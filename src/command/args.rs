@@ -0,0 +1,104 @@
+// Command Arguments
+
+use anyhow::bail;
+
+/// Split INPUT into arguments like shell.
+/// Spaces inside quotes or escaped with backslash are kept.
+pub fn parse_args(input: &str) -> anyhow::Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            },
+
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => bail!("Unclosed single quote in command")
+                    }
+                }
+            },
+
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => arg.push(c),
+                            Some(c) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            },
+                            None => bail!("Unclosed double quote in command")
+                        },
+                        Some(c) => arg.push(c),
+                        None => bail!("Unclosed double quote in command")
+                    }
+                }
+            },
+
+            '\\' => {
+                let arg = current.get_or_insert_with(String::new);
+                if let Some(c) = chars.next() {
+                    arg.push(c);
+                }
+            },
+
+            c => current.get_or_insert_with(String::new).push(c)
+        }
+    }
+
+    if let Some(arg) = current {
+        args.push(arg);
+    }
+
+    Ok(args)
+}
+
+/// Quote ARG to be parsed as a single argument by `parse_args`.
+pub fn quote_arg(arg: &str) -> String {
+    let plain = !arg.is_empty() && !arg.contains([' ', '\t', '\'', '"', '\\']);
+    if plain {
+        return arg.to_owned()
+    }
+
+    // Single quotes are closed, escaped & reopened, like `'Bob'\''s'`.
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(r#"rename  "my file.txt""#).unwrap(),
+            vec!["rename", "my file.txt"]
+        );
+        assert_eq!(
+            parse_args(r#"tab new '/tmp/a b' c\ d "" "say \"hi\"""#).unwrap(),
+            vec!["tab", "new", "/tmp/a b", "c d", "", "say \"hi\""]
+        );
+        assert!(parse_args("rename 'a.txt").is_err());
+    }
+
+    #[test]
+    fn test_quote_arg() {
+        for name in [r#"Bob's  "notes".txt"#, r"back\slash", "plain.txt", "", "'"] {
+            let command = format!("rename {}", quote_arg(name));
+            assert_eq!(parse_args(&command).unwrap(), vec!["rename", name]);
+        }
+        assert_eq!(quote_arg("plain.txt"), "plain.txt");
+    }
+}
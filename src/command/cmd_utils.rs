@@ -6,9 +6,11 @@ use ratatui::DefaultTerminal;
 
 use crate::{
     key_event::{CommandStr, ShellCommand},
+    command::{parse_args, AppCommand, HistoryKind},
     utils::{Block, CmdContent, CursorPos},
     error::{AppResult, ErrorType},
     utils::Direction,
+    option_get,
     rt_error,
     App,
 };
@@ -50,6 +52,10 @@ impl<'a> App<'a> {
             self.command_idx = None;
         }
 
+        if self.history_search.is_some() {
+            self.history_search = None;
+        }
+
         if self.command_expand {
             self.expand_quit();
         }
@@ -67,84 +73,47 @@ impl<'a> App<'a> {
 
     /// The function will change content in command line.
     /// In the meanwhile, adjusting current command index.
+    ///
+    /// The history ring is chosen by the prefix of current content.
     pub fn command_select(&mut self, next: bool) {
         if let Block::CommandLine(
             ref mut current,
             ref mut cursor
         ) = self.selected_block
         {
-            if self.command_history.is_empty() {
+            let content_ref = current.get_mut();
+            let ring = self.command_history.ring(HistoryKind::of(content_ref));
+
+            if ring.is_empty() {
                 return ()
             }
 
-            let content_ref = current.get_mut();
-
             if *cursor != CursorPos::End {
                 *cursor = CursorPos::End;
             }
 
-            // Select with current index when the user has already made this operation
-            if let Some(index) = self.command_idx {
-                if next {
-                    if index == self.command_history.len() - 1 {
+            let index = match self.command_idx {
+                // Select with current index when the user has already made this operation
+                Some(index) if next => {
+                    if index + 1 >= ring.len() {
                         return ()
                     }
-
-                    self.command_idx = Some(index + 1);
-                    *content_ref = self.command_history[index + 1].to_owned();
-                } else {
+                    index + 1
+                },
+                Some(index) => {
                     if index == 0 {
                         return ()
                     }
+                    index - 1
+                },
 
-                    self.command_idx = Some(index - 1);
-                    *content_ref = self.command_history[index - 1].to_owned();
-                }
-
-                return ()
-            }
-
-            // There is no next item
-            if next {
-                return ()
-            }
-
-            // Initial selection.
-            let current_idx = self.command_history
-                .iter()
-                .rev()
-                .position(|x| x == content_ref);
-
-            if let Some(idx) = current_idx {
-                if !next {
-                    // The real idx is: len - 1 - IDX
-                    if idx == self.command_history.len() - 1 {
-                        self.command_idx = Some(0);
-                        return ()
-                    }
-
-                    let temp_idx = self.command_history.len() - 2 - idx;
-                    self.command_idx = Some(temp_idx);
-                    *content_ref = self.command_history[temp_idx].to_owned();
-                } else {
-                    if idx + 1 == self.command_history.len() {
-                        self.command_idx = Some(idx);
-                    } else {
-                        self.command_idx = Some(idx + 1);
-                        *content_ref = self.command_history[idx + 1].to_owned();
-                    }
-                }
+                // There is no next item
+                None if next => return (),
+                None => ring.len() - 1,
+            };
 
-                return ()
-            } 
-
-            if !next {
-                self.command_idx = Some(self.command_history.len() - 1);
-                *content_ref = self.command_history.last().unwrap().to_owned();
-            } else {
-                self.command_idx = Some(0);
-                *content_ref = self.command_history.first().unwrap().to_owned();
-            }
+            self.command_idx = Some(index);
+            *content_ref = ring[index].to_owned();
         }
     }
     
@@ -165,88 +134,153 @@ impl<'a> App<'a> {
 
             let command = content_ref.to_owned();
             self.command_history.push(command.to_owned());
-            let mut command_slices: Vec<&str> = command.split(" ").collect();
 
-            match command_slices[0] {
-                ":rename" => {
-                    if command_slices.len() < 2 {
+            // Shell command
+            if let Some(shell) = command.strip_prefix(":!") {
+                let mut command_slices: Vec<&str> = shell.split(" ").collect();
+                let shell_program = command_slices.remove(0);
+                if command_slices.is_empty() {
+                    rt_error!("{argu_err}")
+                }
+
+                crate::key_event::shell_process(
+                    self,
+                    terminal,
+                    ShellCommand::Command(
+                        Some(shell_program),
+                        CommandStr::from_strs(command_slices)
+                    ),
+                    true
+                )?;
+
+                self.quit_command_mode();
+                return Ok(())
+            }
+
+            let args = match command.strip_prefix(':') {
+                Some(content) => parse_args(content)?,
+                None => return Err(ErrorType::UnvalidCommand.pack())
+            };
+            let name = option_get!(args.first(), "Missing command name");
+            let rest = &args[1..];
+
+            match name.as_str() {
+                "rename" => {
+                    if rest.is_empty() {
                         rt_error!("{argu_err}")
                     }
 
-                    command_slices.remove(0);
-                    let file_name = command_slices.join(" ");
                     super::cmds::rename_file(
                         self.path.to_owned(),
                         self,
-                        file_name
+                        rest.join(" ")
                     )?
                 },
 
-                ":create_file" => {
-                    if command_slices.len() < 2 {
+                "create_file" | "create_dir" => {
+                    if rest.is_empty() {
                         rt_error!("{argu_err}")
                     }
 
-                    command_slices.remove(0);
-                    let files = command_slices.join(" ");
-                    let files: Vec<&str> = files.split(",").collect();
-                    super::cmds::create_file(
+                    // Each argument is a file, names separated with `,` are accepted as well.
+                    let files = rest.iter()
+                        .flat_map(|arg| arg.split(","))
+                        .map(|file| file.trim_start().to_owned())
+                        .collect();
+                    crate::key_event::create_files(
                         self,
-                        files.into_iter(),
-                        false
+                        files,
+                        name == "create_dir"
                     )?
                 },
 
-                ":create_dir" => {
-                    if command_slices.len() < 2 {
+                "create_symlink" => {
+                    if rest.len() != 2 {
                         rt_error!("{argu_err}")
                     }
 
-                    command_slices.remove(0);
-                    let files = command_slices.join(" ");
-                    let files: Vec<&str> = files.split(",").collect();
-                    super::cmds::create_file(
+                    self.marked_files.clear();
+
+                    super::cmds::create_symlink(
+                        self,
+                        [(rest[0].as_str(), rest[1].as_str())].into_iter()
+                    )?
+                },
+
+                // Trash
+                "trash_list" => {
+                    crate::key_event::list_trash(self)?;
+                    return Ok(())
+                },
+
+                "trash_restore" => {
+                    crate::key_event::restore_trash(
                         self,
-                        files.into_iter(),
-                        true
+                        rest.iter().map(String::as_str)
                     )?
                 },
 
-                ":create_symlink" => {
-                    if command_slices.len() != 3 {
+                "trash_empty" => {
+                    crate::key_event::empty_trash(self);
+                    return Ok(())
+                },
+
+                // Archive
+                "compress" => {
+                    if rest.is_empty() {
                         rt_error!("{argu_err}")
                     }
 
-                    self.marked_files.clear();
+                    crate::key_event::compress(self, &rest.join(" "))?;
+                    if self.switch_case.is_some() {
+                        return Ok(())
+                    }
+                },
 
-                    command_slices.remove(0);
-                    let files = command_slices;
-                    super::cmds::create_symlink(
+                // Background jobs
+                "job_cancel" => {
+                    crate::key_event::cancel_jobs(
                         self,
-                        [(files[0], files[1])].into_iter()
+                        rest.iter().map(String::as_str)
                     )?
                 },
 
-                // Shell command
-                shell if shell.starts_with(":!") => {
-                    if command_slices.len() < 2 {
-                        rt_error!("{argu_err}")
+                "tab" => crate::key_event::tab_command(self, rest)?,
+
+                "filter" => {
+                    let pattern = raw_argument(&command, name, rest);
+                    self.set_filter((!pattern.is_empty()).then_some(pattern.as_str()))?
+                },
+
+                "bulk_rename" => {
+                    let operation = raw_argument(&command, name, rest);
+                    crate::key_event::bulk_rename(self, terminal, &operation)?;
+                    if self.switch_case.is_some() {
+                        return Ok(())
                     }
+                },
 
-                    command_slices.remove(0);
-                    let shell_program = &shell[2..];
+                "chmod" => crate::key_event::chmod(self, rest)?,
+                "chown" => crate::key_event::chown(self, rest)?,
 
-                    crate::key_event::shell_process(
-                        self,
-                        terminal,
-                        ShellCommand::Command(
-                            Some(shell_program),
-                            CommandStr::from_strs(command_slices)
-                        ),
-                        true
-                    )?;
+                "grep" => {
+                    let pattern = raw_argument(&command, name, rest);
+                    crate::key_event::grep(self, (!pattern.is_empty()).then_some(pattern.as_str()))?
                 },
-                _ => return Err(ErrorType::UnvalidCommand.pack())
+
+                // Commands used in keymap
+                _ => {
+                    let slices = args.iter()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>();
+                    let command = AppCommand::from_slice(&slices)
+                        .map_err(|_| ErrorType::UnvalidCommand.pack())?;
+
+                    self.quit_command_mode();
+                    command.execute(self, terminal, self.root())?;
+
+                    return Ok(())
+                }
             }
 
             self.quit_command_mode();
@@ -284,3 +318,11 @@ impl<'a> App<'a> {
         }
     }
 }
+
+/// Get the argument of command NAME as it's inputted, REST is used when it cannot be found.
+/// It's used for patterns, since backslashes are common in regex.
+fn raw_argument(command: &str, name: &str, rest: &[String]) -> String {
+    command[1..].trim_start()
+        .strip_prefix(name)
+        .map_or_else(|| rest.join(" "), |argument| argument.trim().to_owned())
+}
@@ -6,6 +6,8 @@ use std::path::{PathBuf, Path};
 use crate::App;
 use crate::rt_error;
 use crate::app::{sort, FileSaver};
+use crate::utils::unique_path;
+use crate::key_event::{move_to_trash, ConflictPolicy, ConflictResolver, FsOperation};
 use crate::error::{AppResult, AppError, ErrorType};
 
 pub fn rename_file(
@@ -32,9 +34,11 @@ pub fn rename_file(
 
     let origin_file = path.join(&file.name);
     let new_file = path.join(&new_name);
-    fs::rename(origin_file, &new_file)?;
+    fs::rename(&origin_file, &new_file)?;
     file.name = new_name.to_owned();
 
+    app.journal.record(FsOperation::Renamed(origin_file, new_file.to_owned()));
+
     if new_name.starts_with(".") && hide_files {
         app.hide_or_show(Some(new_name))?;
         return Ok(())
@@ -42,12 +46,15 @@ pub fn rename_file(
 
     // Refresh modified time
     let metadata = fs::metadata(new_file)?;
-    file.set_modified(metadata.modified().unwrap());
+    if let Some(file) = app.get_file_saver_mut() {
+        file.set_modified(metadata.modified().unwrap());
+    }
 
     // Refresh the display of whole directory
+    let sort_mode = app.sort_config.get(&app.current_path());
     let (directory, index) = app.get_directory_mut();
     let mut new_files = directory.to_owned();
-    sort(&mut new_files);
+    sort(&mut new_files, sort_mode);
     let new_index = new_files
         .iter()
         .position(|x| x.name == new_name)
@@ -58,10 +65,12 @@ pub fn rename_file(
     Ok(())
 }
 
+/// Create FILES in current directory, the existing ones are handled by RESOLVER.
 pub fn create_file<'a, I>(
     app: &mut App,
     files: I,
-    is_dir: bool
+    is_dir: bool,
+    resolver: &ConflictResolver
 ) -> AppResult<()>
 where I: Iterator<Item = &'a str>
 {
@@ -72,9 +81,45 @@ where I: Iterator<Item = &'a str>
     let mut to_show_hidden_files = false;
 
     for file in files {
-        let mut file = file.trim_start().to_owned();
-        if file_exists(path.join(&file))? {
-            file.push_str(".new");
+        let mut file = file.to_owned();
+        let existing = path.join(&file);
+
+        if let Ok(metadata) = existing.symlink_metadata() {
+            match resolver.get(&existing) {
+                ConflictPolicy::Skip => continue,
+
+                ConflictPolicy::Rename => {
+                    let new_path = unique_path(existing);
+                    file = new_path.strip_prefix(&path)
+                        .unwrap_or(&new_path)
+                        .to_string_lossy()
+                        .to_string();
+                },
+
+                _ => {
+                    // A directory is never overwritten by a file, and vice versa.
+                    if metadata.is_dir() != is_dir {
+                        errors.add_error(anyhow::anyhow!(
+                            "Cannot overwrite {} with a {}",
+                            existing.to_string_lossy(),
+                            if is_dir { "directory" } else { "file" }
+                        ));
+                        continue;
+                    }
+
+                    match move_to_trash(&existing) {
+                        Ok(entry) => app.journal.record(FsOperation::Trashed(entry)),
+                        Err(err) => {
+                            errors.add_error(err);
+                            continue;
+                        }
+                    }
+
+                    // The overwritten one is removed from file list.
+                    let (dir, _) = app.get_directory_mut();
+                    dir.retain(|_file| _file.name != file);
+                }
+            }
         }
 
         if is_dir {
@@ -82,11 +127,14 @@ where I: Iterator<Item = &'a str>
                 Err(err) => {
                     errors.add_error(err);
                 },
-                Ok(_) => new_files.push(FileSaver::new(
-                    file.to_owned(),
-                    path.join(&file),
-                    None
-                ))
+                Ok(_) => {
+                    app.journal.record(FsOperation::Created(path.join(&file)));
+                    new_files.push(FileSaver::new(
+                        file.to_owned(),
+                        path.join(&file),
+                        None
+                    ))
+                }
             }
         } else {
             let file_create = fs::File::create(
@@ -94,6 +142,7 @@ where I: Iterator<Item = &'a str>
             );
             match file_create {
                 Ok(file_create) => {
+                    app.journal.record(FsOperation::Created(path.join(&file)));
                     new_files.push(FileSaver::new(
                         file.to_owned(),
                         path.join(&file),
@@ -119,9 +168,10 @@ where I: Iterator<Item = &'a str>
             prev_file_name = current_file.name.to_owned();
         }
 
+        let sort_mode = app.sort_config.get(&path);
         let (dir, idx) = app.get_directory_mut();
         dir.extend(new_files.into_iter());
-        sort(dir);
+        sort(dir, sort_mode);
         if prev_file_name.is_empty() {
             idx.select(Some(0));
             if app.path.to_string_lossy() == "/" {
@@ -174,6 +224,10 @@ where
                 errors.add_error(err);
             },
             _ => {
+                app.journal.record(FsOperation::Created(
+                    target.as_ref().to_path_buf()
+                ));
+
                 if !to_show_hidden_files
                     && target.as_ref().file_name().unwrap().to_string_lossy().starts_with(".")
                     && app.hide_files
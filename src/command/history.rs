@@ -0,0 +1,197 @@
+// Command History
+
+use std::fs;
+
+use anyhow::bail;
+use toml_edit::{value, Array, DocumentMut};
+
+use crate::config::get_document;
+
+/// The kind of command line content, each kind has its own history ring.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HistoryKind {
+    /// Commands starting with `:`.
+    Command,
+    /// File searches starting with `/`.
+    Search,
+    /// Shell commands starting with `:!`.
+    Shell,
+}
+
+impl HistoryKind {
+    pub fn of(entry: &str) -> Self {
+        if entry.starts_with(":!") {
+            HistoryKind::Shell
+        } else if entry.starts_with('/') {
+            HistoryKind::Search
+        } else {
+            HistoryKind::Command
+        }
+    }
+
+    /// The key of the ring in history file.
+    fn key(self) -> &'static str {
+        match self {
+            HistoryKind::Command => "commands",
+            HistoryKind::Search  => "searches",
+            HistoryKind::Shell   => "shells",
+        }
+    }
+}
+
+const KINDS: [HistoryKind; 3] = [HistoryKind::Command, HistoryKind::Search, HistoryKind::Shell];
+
+/// History of command line, the newest entry is at the end of each ring.
+pub struct CommandHistory {
+    commands: Vec<String>,
+    searches: Vec<String>,
+    shells: Vec<String>,
+
+    /// The max number of entries in each ring.
+    limit: usize,
+
+    /// Path of history file, history is not saved when it's None.
+    path: Option<String>,
+
+    /// Entries added in this session, which are merged into history file when saving.
+    session: Vec<String>,
+}
+
+impl Default for CommandHistory {
+    fn default() -> Self {
+        CommandHistory {
+            commands: Vec::new(),
+            searches: Vec::new(),
+            shells: Vec::new(),
+            limit: 1000,
+            path: None,
+            session: Vec::new(),
+        }
+    }
+}
+
+impl CommandHistory {
+    /// Load history from PATH, keeping at most LIMIT entries in each ring.
+    pub fn load(path: String, limit: usize) -> anyhow::Result<Self> {
+        let mut history = CommandHistory {
+            limit,
+            ..Default::default()
+        };
+        history.read_document(&get_document(path.to_owned())?)?;
+        history.path = Some(path);
+
+        Ok(history)
+    }
+
+    pub fn ring(&self, kind: HistoryKind) -> &Vec<String> {
+        match kind {
+            HistoryKind::Command => &self.commands,
+            HistoryKind::Search  => &self.searches,
+            HistoryKind::Shell   => &self.shells,
+        }
+    }
+
+    fn ring_mut(&mut self, kind: HistoryKind) -> &mut Vec<String> {
+        match kind {
+            HistoryKind::Command => &mut self.commands,
+            HistoryKind::Search  => &mut self.searches,
+            HistoryKind::Shell   => &mut self.shells,
+        }
+    }
+
+    /// Push ENTRY into its ring. The duplicate one is moved to the end.
+    pub fn push(&mut self, entry: String) {
+        // Nothing is inputted except the prefix.
+        if matches!(entry.trim(), "" | ":" | "/" | ":!") {
+            return
+        }
+
+        self.session.push(entry.to_owned());
+        self.insert(entry);
+    }
+
+    fn insert(&mut self, entry: String) {
+        let limit = self.limit;
+        let ring = self.ring_mut(HistoryKind::of(&entry));
+
+        ring.retain(|item| *item != entry);
+        ring.push(entry);
+
+        if ring.len() > limit {
+            ring.drain(..ring.len() - limit);
+        }
+    }
+
+    fn read_document(&mut self, document: &DocumentMut) -> anyhow::Result<()> {
+        for kind in KINDS.into_iter() {
+            let Some(item) = document.get(kind.key()) else {
+                continue
+            };
+
+            let Some(entries) = item.as_array() else {
+                bail!("Wrong type for {} in history file", kind.key())
+            };
+
+            for entry in entries.iter() {
+                match entry.as_str() {
+                    // Entries in the wrong ring are ignored.
+                    Some(entry) if HistoryKind::of(entry) == kind => {
+                        self.insert(entry.to_owned())
+                    },
+                    Some(_) => (),
+                    None => bail!("Wrong type for {} in history file", kind.key())
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Save history into the history file.
+    /// The file is read again, so that the history of other hire instances is kept.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(())
+        };
+
+        let mut history = CommandHistory {
+            limit: self.limit,
+            ..Default::default()
+        };
+        history.read_document(&get_document(path.to_owned())?)?;
+        for entry in self.session.iter() {
+            history.insert(entry.to_owned());
+        }
+
+        let mut document = DocumentMut::new();
+        for kind in KINDS.into_iter() {
+            document[kind.key()] = value(
+                history.ring(kind).iter().map(String::as_str).collect::<Array>()
+            );
+        }
+        fs::write(path, document.to_string())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_push() {
+        let mut history = CommandHistory {
+            limit: 2,
+            ..Default::default()
+        };
+
+        for entry in [":tab next", "/foo", ":!ls -l", ":refresh", ":tab next", ":", ":quit"] {
+            history.push(entry.to_owned());
+        }
+
+        assert_eq!(history.ring(HistoryKind::Command), &vec![":tab next", ":quit"]);
+        assert_eq!(history.ring(HistoryKind::Search), &vec!["/foo"]);
+        assert_eq!(history.ring(HistoryKind::Shell), &vec![":!ls -l"]);
+    }
+}
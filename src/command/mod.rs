@@ -1,8 +1,12 @@
 // Command
 
 mod cmds;
+mod args;
 mod types;
+mod history;
 mod cmd_utils;
 
 pub use cmds::*;
-pub use types::AppCommand;
+pub use args::{parse_args, quote_arg};
+pub use types::{AppCommand, COMMAND_NAMES};
+pub use history::{CommandHistory, HistoryKind};
@@ -2,33 +2,57 @@
 
 use anyhow::bail;
 
-use crate::{option_get, utils::Direction};
+use crate::{key_event::SearchMode, option_get, utils::Direction};
 
 #[derive(Clone)]
 pub enum AppCommand {
     Tab,
     Goto,
     Shell,
+    Undo,
+    Redo,
+    JobList,
+    OpenWith,
     Paste,
     Delete,
     Search,
     Refresh,
     NextTab,
     PrevTab,
-    FzfJump,
+    Finder,
     CmdShell,
     EditMode,
     CreateDir,
     CreateFile,
     GotoBottom,
     HideOrShow,
+    IgnoreOrShow,
     OutputFile,
     MarkExpand,
     ShowNaviIndex,
+    PermanentDelete,
     SingleSymlink,
     PrintFullPath,
     CommandInsert,
     QuitAfterOutput,
+    Confirm,
+    HideCompletion,
+    HistorySearch,
+    MacroRecord,
+    Filter,
+    FilterClear,
+    BulkRename,
+    DirSize,
+    DiskUsage,
+
+    /// Play the macro in the register, the register is asked when it's None.
+    MacroPlay(Option<char>),
+
+    /// Commands run in order.
+    Chain(Vec<AppCommand>),
+
+    /// Open sort menu, or sort with the key of menu directly.
+    Sort(Option<char>),
 
     /// When the boolean is true, only mark single file.
     Mark(bool),
@@ -49,6 +73,9 @@ pub enum AppCommand {
     /// Move cursor to the candidate, jumping to the next when the boolean is true.
     MoveCandidate(bool),
 
+    /// Set the default search mode, switch to the next one when it's None.
+    SearchMode(Option<SearchMode>),
+
     /// When the boolean value is true, set the working directory;
     /// otherwise jump to the working directory.
     WorkDirectory(bool),
@@ -57,6 +84,17 @@ pub enum AppCommand {
     /// The value of it is the direction for movement,
     ItemMove(Direction),
 
+    /// Move cursor of command line or the item being edited.
+    /// The first boolean is true to move right, the second one to move to the edge.
+    CursorMove(bool, bool),
+
+    /// Select the next completion candidate or history command when the boolean is true.
+    SelectCandidate(bool),
+
+    /// Scroll the expanded command line, otherwise select history command
+    /// or move cursor with the direction.
+    CommandScroll(Direction),
+
     /// The first element is the shell command with its arguments,
     /// the second element refers to whether refreshing showing file items.
     ShellCommand(Vec<String>, bool),
@@ -85,113 +123,266 @@ pub enum AppCommand {
     EditListScroll(bool),
 }
 
+/// Define `COMMAND_NAMES` and `parse_command` from the same table, so that they cannot drift.
+/// SLICE, ARG & ERR are the names of command slice, the first argument & the error message,
+/// which can be used in the expressions creating commands.
+macro_rules! command_table {
+    (
+        $slice:ident, $arg:ident, $err:ident;
+        $( $name:literal $(| $alias:literal)* => $command:expr ),* $(,)?
+    ) => {
+        /// Names of commands used in keymap & command line.
+        pub const COMMAND_NAMES: &[&str] = &[$( $name ),*];
+
+        fn parse_command($slice: &[&str]) -> anyhow::Result<AppCommand> {
+            let $err = "Unknow command for binding";
+            let $arg = $slice.get(1);
+
+            let name = *option_get!($slice.get(0), $err);
+            let command = match name {
+                $( $name $(| $alias)* => $command, )*
+                _ => bail!("Unknow command: {}", name)
+            };
+
+            Ok(command)
+        }
+    };
+}
+
+command_table! {
+    command_slice, cmd_arg, command_err;
+
+    "tab_operation"     => AppCommand::Tab,
+    "goto_operation"    => AppCommand::Goto,
+    "spawn_shell"       => AppCommand::Shell,
+    "undo"              => AppCommand::Undo,
+    "redo"              => AppCommand::Redo,
+    "job_list"          => AppCommand::JobList,
+    "open_with"         => AppCommand::OpenWith,
+    "paste_operation"   => AppCommand::Paste,
+    "delete_operation"  => AppCommand::Delete,
+    "permanent_delete"  => AppCommand::PermanentDelete,
+    "search"            => AppCommand::Search,
+    // `fzf_jump` is kept for the keymaps written before.
+    "finder" | "fzf_jump" => AppCommand::Finder,
+    "refresh"           => AppCommand::Refresh,
+    "next_tab"          => AppCommand::NextTab,
+    "prev_tab"          => AppCommand::PrevTab,
+    "cmdline_shell"     => AppCommand::CmdShell,
+    "edit_mode"         => AppCommand::EditMode,
+    "create_dir"        => AppCommand::CreateDir,
+    "create_file"       => AppCommand::CreateFile,
+    "goto_bottom"       => AppCommand::GotoBottom,
+    "hide_or_show"      => AppCommand::HideOrShow,
+    "ignore_or_show"    => AppCommand::IgnoreOrShow,
+    "mark_expand"       => AppCommand::MarkExpand,
+    "output_file"       => AppCommand::OutputFile,
+    "full_path"         => AppCommand::PrintFullPath,
+    "single_symlink"    => AppCommand::SingleSymlink,
+    "show_navi_index"   => AppCommand::ShowNaviIndex,
+    "command_insert"    => AppCommand::CommandInsert,
+    "quit_after_output" => AppCommand::QuitAfterOutput,
+    "confirm"           => AppCommand::Confirm,
+    "hide_completion"   => AppCommand::HideCompletion,
+    "history_search"    => AppCommand::HistorySearch,
+    "macro_record"      => AppCommand::MacroRecord,
+    "filter"            => AppCommand::Filter,
+    "filter_clear"      => AppCommand::FilterClear,
+    "bulk_rename"       => AppCommand::BulkRename,
+    "dir_size"          => AppCommand::DirSize,
+    "disk_usage"        => AppCommand::DiskUsage,
+
+    "macro_play" => AppCommand::MacroPlay(
+        cmd_arg.and_then(|register| register.chars().next())
+    ),
+
+    "sort" => AppCommand::Sort(match cmd_arg {
+        Some(mode) => Some(sort_key(mode, command_slice.get(2))?),
+        None => None
+    }),
+
+    "list_scroll" => AppCommand::ListScroll(
+        *option_get!(cmd_arg, command_err) == "next"
+    ),
+
+    "move" => AppCommand::ItemMove(Direction::from_str(
+        option_get!(cmd_arg, command_err)
+    )?),
+
+    "cursor_move" => {
+        let (right, edge) = match *option_get!(cmd_arg, command_err) {
+            "left"  => (false, false),
+            "right" => (true, false),
+            "begin" => (false, true),
+            "end"   => (true, true),
+            _ => bail!("{}", command_err)
+        };
+
+        AppCommand::CursorMove(right, edge)
+    },
+
+    "select_candidate" => AppCommand::SelectCandidate(
+        *option_get!(cmd_arg, command_err) == "next"
+    ),
+
+    "command_scroll" => AppCommand::CommandScroll(Direction::from_str(
+        option_get!(cmd_arg, command_err)
+    )?),
+
+    "move_candidate" => AppCommand::MoveCandidate(
+        *option_get!(cmd_arg, command_err) == "next"
+    ),
+
+    "search_mode" => AppCommand::SearchMode(match cmd_arg {
+        Some(mode) => Some(SearchMode::from_str(mode)?),
+        None => None
+    }),
+
+    "mark" => AppCommand::Mark(
+        *option_get!(cmd_arg, command_err) == "single"
+    ),
+
+    "work_directory" => AppCommand::WorkDirectory(
+        *option_get!(cmd_arg, command_err) == "set"
+    ),
+
+    "append_filename" => AppCommand::AppendFsName(
+        *option_get!(cmd_arg, command_err) == "extension"
+    ),
+
+    "navi_input" => AppCommand::NaviIndexInput(
+        option_get!(cmd_arg, command_err).parse::<u8>()?
+    ),
+
+    "shell_command" => {
+        let refresh = *option_get!(cmd_arg, command_err) == "true";
+        let command_vec = command_slice[2..].into_iter()
+            .map(|_str| (*_str).to_owned())
+            .collect::<Vec<_>>();
+
+        AppCommand::ShellCommand(command_vec, refresh)
+    },
+
+    "switch_tab" => {
+        let _str = *option_get!(cmd_arg, command_err);
+        if _str.len() < 1 {
+            bail!("{}", command_err)
+        } else {
+            AppCommand::SwitchTab(_str.chars().next().unwrap())
+        }
+    },
+
+    // Edit Mode
+    "quit_edit"   => AppCommand::QuitEdit,
+    "edit_delete" => AppCommand::EditDelete,
+    "edit_top"    => AppCommand::EditGotoTop,
+    "edit_bottom" => AppCommand::EditGotoBottom,
+
+    "edit_new" => AppCommand::EditNew(
+        *option_get!(cmd_arg, command_err) == "dir"
+    ),
+
+    "edit_mark" => AppCommand::EditMark(
+        *option_get!(cmd_arg, command_err) == "single"
+    ),
+
+    "edit_move" => AppCommand::EditMoveItem(
+        *option_get!(cmd_arg, command_err) == "next"
+    ),
+
+    "edit_insert" => AppCommand::EditInsert(
+        *option_get!(cmd_arg, command_err) == "end"
+    ),
+
+    "edit_list_scroll" => AppCommand::EditListScroll(
+        *option_get!(cmd_arg, command_err) == "next"
+    ),
+}
+
 impl AppCommand {
     pub fn from_str(value: &str) -> anyhow::Result<Self> {
-        let command_err = "Unknow command for binding";
-        let command_slice = value.split(" ")
-            .collect::<Vec<_>>();
+        let value = value.trim();
 
-        let cmd_arg = command_slice.get(1);
-        let command = match *option_get!(command_slice.get(0), command_err) {
-            "tab_operation"     => Self::Tab,
-            "goto_operation"    => Self::Goto,
-            "spawn_shell"       => Self::Shell,
-            "paste_operation"   => Self::Paste,
-            "delete_operation"  => Self::Delete,
-            "search"            => Self::Search,
-            "fzf_jump"          => Self::FzfJump,
-            "refresh"           => Self::Refresh,
-            "next_tab"          => Self::NextTab,
-            "prev_tab"          => Self::PrevTab,
-            "cmdline_shell"     => Self::CmdShell,
-            "edit_mode"         => Self::EditMode,
-            "create_dir"        => Self::CreateDir,
-            "create_file"       => Self::CreateFile,
-            "goto_bottom"       => Self::GotoBottom,
-            "hide_or_show"      => Self::HideOrShow,
-            "mark_expand"       => Self::MarkExpand,
-            "output_file"       => Self::OutputFile,
-            "full_path"         => Self::PrintFullPath,
-            "single_symlink"    => Self::SingleSymlink,
-            "show_navi_index"   => Self::ShowNaviIndex,
-            "command_insert"    => Self::CommandInsert,
-            "quit_after_output" => Self::QuitAfterOutput,
-
-            "list_scroll" => Self::ListScroll(
-                *option_get!(cmd_arg, command_err) == "next"
-            ),
-
-            "move" => Self::ItemMove(Direction::from_str(
-                option_get!(cmd_arg, command_err)
-            )?),
-
-            "move_candidate" => Self::MoveCandidate(
-                *option_get!(cmd_arg, command_err) == "next"
-            ),
-
-            "mark" => Self::Mark(
-                *option_get!(cmd_arg, command_err) == "single"
-            ),
-
-            "work_directory" => Self::WorkDirectory(
-                *option_get!(cmd_arg, command_err) == "set"
-            ),
-
-            "append_filename" => Self::AppendFsName(
-                *option_get!(cmd_arg, command_err) == "extension"
-            ),
-
-            "navi_input" => Self::NaviIndexInput(
-                option_get!(cmd_arg, command_err).parse::<u8>()?
-            ),
-
-            "shell_command" => {
-                let refresh = *option_get!(cmd_arg, command_err) == "true";
-                let command_vec = command_slice[2..].into_iter()
-                    .map(|_str| (*_str).to_owned())
-                    .collect::<Vec<_>>();
-
-                Self::ShellCommand(command_vec, refresh)
-            },
-
-            "switch_tab" => {
-                let _str = *option_get!(cmd_arg, command_err);
-                if _str.len() < 1 {
-                    bail!("{}", command_err)
-                } else {
-                    Self::SwitchTab(_str.chars().next().unwrap())
-                }
-            },
-
-            // Edit Mode
-            "quit_edit"   => Self::QuitEdit,
-            "edit_delete" => Self::EditDelete,
-            "edit_top"    => Self::EditGotoTop,
-            "edit_bottom" => Self::EditGotoBottom,
-
-            "edit_new" => Self::EditNew(
-                *option_get!(cmd_arg, command_err) == "dir"
-            ),
-
-            "edit_mark" => Self::EditMark(
-                *option_get!(cmd_arg, command_err) == "single"
-            ),
-
-            "edit_move" => Self::EditMoveItem(
-                *option_get!(cmd_arg, command_err) == "next"
-            ),
-
-            "edit_insert" => Self::EditInsert(
-                *option_get!(cmd_arg, command_err) == "end"
-            ),
-
-            "edit_list_scroll" => Self::EditListScroll(
-                *option_get!(cmd_arg, command_err) == "next"
-            ),
-
-            _ => bail!("Unknow command for keybinding")
-        };
+        // Commands separated with `;` are chained,
+        // while a shell command takes the rest of string.
+        let chain = value.split_once(';')
+            .filter(|_| !value.starts_with("shell_command"));
+        if let Some((first, rest)) = chain {
+            return Self::from_list([first, rest].into_iter())
+        }
+
+        Self::from_slice(&value.split(" ").collect::<Vec<_>>())
+    }
+
+    /// Create command from its name & arguments.
+    pub fn from_slice(command_slice: &[&str]) -> anyhow::Result<Self> {
+        parse_command(command_slice)
+    }
+
+    /// Create command chain from VALUES.
+    pub fn from_list<'a, I>(values: I) -> anyhow::Result<Self>
+    where I: Iterator<Item = &'a str>
+    {
+        let mut commands = Vec::new();
+        for value in values {
+            match Self::from_str(value)? {
+                Self::Chain(chain) => commands.extend(chain),
+                command => commands.push(command)
+            }
+        }
+
+        if commands.is_empty() {
+            bail!("The command chain cannot be empty")
+        }
+
+        Ok(Self::Chain(commands))
+    }
+}
+
+/// Get the key of sort menu with MODE, GLOBAL is used to modify the global sort mode.
+fn sort_key(mode: &str, global: Option<&&str>) -> anyhow::Result<char> {
+    let key = match mode {
+        "name"        => 'n',
+        "ignore_case" => 'i',
+        "natural"     => 'v',
+        "size"        => 's',
+        "modified"    => 'm',
+        "extension"   => 'e',
+        "reverse"     => 'r',
+        "clear"       => 'c',
+        _ => bail!("Unknow sort mode: {}", mode)
+    };
+
+    Ok(match global {
+        Some(&"global") => key.to_ascii_uppercase(),
+        Some(_) => bail!("Unknow sort scope, it could only be global"),
+        None => key
+    })
+}
 
-        Ok(command)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_names() {
+        // Commands requiring arguments are tried with each of them.
+        let args: [&[&str]; 7] = [&[], &["next"], &["up"], &["left"], &["1"], &["true", "ls"], &["a"]];
+
+        for name in COMMAND_NAMES.iter() {
+            let parsed = args.iter().any(|args| {
+                let slice: Vec<&str> = [*name].iter().chain(args.iter()).copied().collect();
+                AppCommand::from_slice(&slice).is_ok()
+            });
+            assert!(parsed, "{}", name);
+        }
+
+        let mut names = COMMAND_NAMES.to_vec();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), COMMAND_NAMES.len());
+
+        assert!(AppCommand::from_slice(&["fzf_jump"]).is_ok());
+        assert!(AppCommand::from_slice(&["unknown"]).is_err());
     }
 }
@@ -0,0 +1,259 @@
+// Key Sequence
+
+use std::fmt;
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::command::AppCommand;
+
+/// A single key press with its modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers = modifiers;
+
+        // Shift is already implied by the character itself.
+        match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers.remove(KeyModifiers::SHIFT),
+            _ => ()
+        }
+
+        Key { code, modifiers }
+    }
+
+    /// Whether the key is a character without modifiers, which can be inserted as text.
+    pub fn is_plain_char(&self) -> bool {
+        self.plain_char().is_some()
+    }
+
+    pub fn plain_char(&self) -> Option<char> {
+        match self.code {
+            KeyCode::Char(c) if self.modifiers.is_empty() => Some(c),
+            _ => None
+        }
+    }
+
+    /// Parse vim-style key sequence, such as `gg`, `<C-d>`, `<A-Left>` & `<F5>`.
+    pub fn parse_sequence(sequence: &str) -> Result<Vec<Key>> {
+        let mut keys = Vec::new();
+        let mut rest = sequence;
+
+        while let Some(c) = rest.chars().next() {
+            let special = rest.strip_prefix('<')
+                .and_then(|_| rest.find('>'))
+                .filter(|end| *end > 1);
+            if let Some(end) = special {
+                keys.push(Self::parse_special(&rest[1..end])?);
+                rest = &rest[end + 1..];
+                continue;
+            }
+
+            keys.push(Key::new(KeyCode::Char(c), KeyModifiers::NONE));
+            rest = &rest[c.len_utf8()..];
+        }
+
+        if keys.is_empty() {
+            bail!("The key sequence cannot be empty")
+        }
+
+        Ok(keys)
+    }
+
+    /// Parse the content of `<...>`.
+    fn parse_special(content: &str) -> Result<Key> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut name = content;
+
+        // The last character of name could be `-`, like `<C-->`.
+        while name.len() > 2 && name.as_bytes()[1] == b'-' {
+            modifiers |= match name.as_bytes()[0].to_ascii_uppercase() {
+                b'C' => KeyModifiers::CONTROL,
+                b'A' | b'M' => KeyModifiers::ALT,
+                b'S' => KeyModifiers::SHIFT,
+                _ => bail!("Unknown key modifier in <{}>", content)
+            };
+            name = &name[2..];
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            },
+
+            _ => match name.to_lowercase().as_str() {
+                "cr" | "enter" | "return" => KeyCode::Enter,
+                "esc"                     => KeyCode::Esc,
+                "bs" | "backspace"        => KeyCode::Backspace,
+                "del" | "delete"          => KeyCode::Delete,
+                "insert"                  => KeyCode::Insert,
+                "space"                   => KeyCode::Char(' '),
+                "lt"                      => KeyCode::Char('<'),
+                "up"                      => KeyCode::Up,
+                "down"                    => KeyCode::Down,
+                "left"                    => KeyCode::Left,
+                "right"                   => KeyCode::Right,
+                "home"                    => KeyCode::Home,
+                "end"                     => KeyCode::End,
+                "pageup"                  => KeyCode::PageUp,
+                "pagedown"                => KeyCode::PageDown,
+                "tab" => {
+                    if modifiers.contains(KeyModifiers::SHIFT) {
+                        KeyCode::BackTab
+                    } else {
+                        KeyCode::Tab
+                    }
+                },
+
+                function => match function.strip_prefix('f').map(str::parse::<u8>) {
+                    Some(Ok(idx)) if (1..=12).contains(&idx) => KeyCode::F(idx),
+                    _ => bail!("Unknown key <{}>", content)
+                }
+            }
+        };
+
+        Ok(Key::new(code, modifiers))
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Key::new(event.code, event.modifiers)
+    }
+}
+
+impl From<Key> for KeyEvent {
+    fn from(key: Key) -> Self {
+        KeyEvent::new(key.code, key.modifiers)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.code {
+            KeyCode::Char(' ')        => String::from("Space"),
+            KeyCode::Char('<')        => String::from("lt"),
+            KeyCode::Char(c)          => c.to_string(),
+            KeyCode::Enter            => String::from("CR"),
+            KeyCode::Esc              => String::from("Esc"),
+            KeyCode::Backspace        => String::from("BS"),
+            KeyCode::Delete           => String::from("Del"),
+            KeyCode::Insert           => String::from("Insert"),
+            KeyCode::Up               => String::from("Up"),
+            KeyCode::Down             => String::from("Down"),
+            KeyCode::Left             => String::from("Left"),
+            KeyCode::Right            => String::from("Right"),
+            KeyCode::Home             => String::from("Home"),
+            KeyCode::End              => String::from("End"),
+            KeyCode::PageUp           => String::from("PageUp"),
+            KeyCode::PageDown         => String::from("PageDown"),
+            KeyCode::Tab              => String::from("Tab"),
+            KeyCode::BackTab          => String::from("S-Tab"),
+            KeyCode::F(idx)           => format!("F{}", idx),
+            _                         => String::from("?"),
+        };
+
+        if self.is_plain_char() && name.chars().count() == 1 {
+            return write!(f, "{}", name)
+        }
+
+        write!(f, "<")?;
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "C-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "A-")?;
+        }
+        write!(f, "{}>", name)
+    }
+}
+
+/// Trie of key sequences, each node could hold a command.
+#[derive(Default)]
+pub struct KeyTrie {
+    command: Option<AppCommand>,
+    children: HashMap<Key, KeyTrie>,
+}
+
+impl KeyTrie {
+    pub fn insert(&mut self, keys: &[Key], command: AppCommand) {
+        let mut node = self;
+        for key in keys.iter() {
+            node = node.children.entry(*key).or_default();
+        }
+
+        node.command = Some(command);
+    }
+
+    /// Get the node of KEYS.
+    pub fn get(&self, keys: &[Key]) -> Option<&KeyTrie> {
+        let mut node = self;
+        for key in keys.iter() {
+            node = node.children.get(key)?;
+        }
+
+        Some(node)
+    }
+
+    pub fn command(&self) -> Option<&AppCommand> {
+        self.command.as_ref()
+    }
+
+    /// Whether the node is the prefix of other sequences, which requires more keys.
+    pub fn is_prefix(&self) -> bool {
+        !self.children.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sequence() {
+        let keys = Key::parse_sequence("g<C-d><A-Left><F5><S-Tab><lt>").unwrap();
+        assert_eq!(keys, vec![
+            Key::new(KeyCode::Char('g'), KeyModifiers::NONE),
+            Key::new(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            Key::new(KeyCode::Left, KeyModifiers::ALT),
+            Key::new(KeyCode::F(5), KeyModifiers::NONE),
+            Key::new(KeyCode::BackTab, KeyModifiers::NONE),
+            Key::new(KeyCode::Char('<'), KeyModifiers::NONE),
+        ]);
+
+        assert_eq!(Key::parse_sequence("<").unwrap().len(), 1);
+        assert_eq!(Key::parse_sequence("<>").unwrap().len(), 2);
+        assert!(Key::parse_sequence("<Foo>").is_err());
+        assert!(Key::parse_sequence("").is_err());
+
+        let display = keys.iter().map(Key::to_string).collect::<String>();
+        assert_eq!(display, "g<C-d><A-Left><F5><S-Tab><lt>");
+    }
+
+    #[test]
+    fn test_key_trie() {
+        let mut trie = KeyTrie::default();
+        trie.insert(&Key::parse_sequence("gg").unwrap(), AppCommand::GotoBottom);
+        trie.insert(&Key::parse_sequence("<C-d>").unwrap(), AppCommand::Refresh);
+
+        let prefix = trie.get(&Key::parse_sequence("g").unwrap()).unwrap();
+        assert!(prefix.is_prefix() && prefix.command().is_none());
+
+        let node = trie.get(&Key::parse_sequence("gg").unwrap()).unwrap();
+        assert!(!node.is_prefix() && node.command().is_some());
+
+        assert!(trie.get(&Key::parse_sequence("<C-d>").unwrap()).is_some());
+        assert!(trie.get(&Key::parse_sequence("d").unwrap()).is_none());
+    }
+}
@@ -1,39 +1,57 @@
 // Keymap
 
-use std::collections::HashMap;
-
-use crate::option_get;
 use crate::{error::{AppError, AppResult}, command::AppCommand, app::App};
 
 use super::get_document;
+use super::key_sequence::{Key, KeyTrie};
+
+/// Key bindings which used to be hard-coded, they can be overridden in keymap.toml.
+const BUILTIN_KEYMAP: [(&str, &str); 16] = [
+    ("<CR>",      "confirm"),
+    ("<C-g>",     "hide_completion"),
+    ("<C-r>",     "history_search"),
+    ("<C-s>",     "search_mode"),
+    ("<C-b>",     "cursor_move left"),
+    ("<C-f>",     "cursor_move right"),
+    ("<C-a>",     "cursor_move begin"),
+    ("<C-e>",     "cursor_move end"),
+    ("<Left>",    "cursor_move left"),
+    ("<Right>",   "cursor_move right"),
+    ("<C-n>",     "select_candidate next"),
+    ("<C-p>",     "select_candidate prev"),
+    ("<Up>",      "command_scroll up"),
+    ("<Down>",    "command_scroll down"),
+    ("<A-Left>",  "command_scroll left"),
+    ("<A-Right>", "command_scroll right"),
+];
+
+#[derive(Clone, Copy)]
+pub enum KeymapMode {
+    Navi,
+    Edit,
+    Normal,
+
+    /// Inputting in command line or edit mode.
+    Input,
+}
 
 #[derive(Default)]
 pub struct Keymap {
-    navi_maps: HashMap<char, AppCommand>,
-    edit_maps: HashMap<char, AppCommand>,
-    normal_maps: HashMap<char, AppCommand>
+    navi_maps: KeyTrie,
+    edit_maps: KeyTrie,
+    normal_maps: KeyTrie,
+    input_maps: KeyTrie,
 }
 
 impl Keymap {
-    pub fn get(&self, key: char) -> anyhow::Result<AppCommand> {
-        Ok(
-            option_get!(self.normal_maps.get(&key), "Invalid keybinding")
-                .to_owned()
-        )
-    }
-
-    pub fn navi_get(&self, key: char) -> anyhow::Result<AppCommand> {
-        Ok(
-            option_get!(self.navi_maps.get(&key), "Invalid keybinding")
-                .to_owned()
-        )
-    }
-
-    pub fn edit_get(&self, key: char) -> anyhow::Result<AppCommand> {
-        Ok(
-            option_get!(self.edit_maps.get(&key), "Invalid keybinding")
-                .to_owned()
-        )
+    /// Get the node of KEYS in the keymap of MODE.
+    pub fn get(&self, mode: KeymapMode, keys: &[Key]) -> Option<&KeyTrie> {
+        match mode {
+            KeymapMode::Navi   => self.navi_maps.get(keys),
+            KeymapMode::Edit   => self.edit_maps.get(keys),
+            KeymapMode::Normal => self.normal_maps.get(keys),
+            KeymapMode::Input  => self.input_maps.get(keys),
+        }
     }
 }
 
@@ -41,6 +59,11 @@ pub fn init_keymap(app: &mut App, path: String) -> AppResult<()> {
     let err_msg = "The format of content in keymap.toml is error";
     let mut errors = AppError::new();
 
+    for (key, bind) in BUILTIN_KEYMAP.into_iter() {
+        let command = AppCommand::from_str(bind).expect("Invalid built-in keybinding");
+        insert_keybinding(app, key, command)?;
+    }
+
     let document = get_document(path)?;
     let keymap = document.get("keymap")
         .expect(err_msg)
@@ -53,14 +76,18 @@ pub fn init_keymap(app: &mut App, path: String) -> AppResult<()> {
             .expect(err_msg)
             .as_str()
             .expect(err_msg);
-        let bind = entry.get("run")
-            .expect(err_msg)
-            .as_str()
-            .expect(err_msg);
-
-        match AppCommand::from_str(bind) {
-            Ok(command) => insert_keybinding(app, key, command, err_msg),
-            Err(err) => errors.add_error(err)
+        let bind = entry.get("run").expect(err_msg);
+
+        // An array of commands is run in order.
+        let command = if let Some(list) = bind.as_array() {
+            AppCommand::from_list(list.iter().map(|value| value.as_str().expect(err_msg)))
+        } else {
+            AppCommand::from_str(bind.as_str().expect(err_msg))
+        };
+
+        let result = command.and_then(|command| insert_keybinding(app, key, command));
+        if let Err(err) = result {
+            errors.add_error(err);
         }
     }
 
@@ -74,20 +101,26 @@ pub fn init_keymap(app: &mut App, path: String) -> AppResult<()> {
 fn insert_keybinding(
     app: &mut App,
     key: &str,
-    command: AppCommand,
-    err_msg: &str
-)
+    command: AppCommand
+) -> anyhow::Result<()>
 {
-    let key_char = key.chars().next().expect(err_msg);
-    
-    match command {
+    let keys = Key::parse_sequence(key)?;
+    let keymap = &mut app.keymap;
+
+    // The mode of command chain is decided by its first command.
+    let mode_command = match command {
+        AppCommand::Chain(ref chain) => chain[0].to_owned(),
+        ref command => command.to_owned()
+    };
+
+    match mode_command {
         AppCommand::NaviIndexInput(_) => {
-            app.keymap.navi_maps.insert(key_char, command);
+            keymap.navi_maps.insert(&keys, command);
         },
 
         AppCommand::ShowNaviIndex | AppCommand::MarkExpand => {
-            app.keymap.edit_maps.insert(key_char, command.to_owned());
-            app.keymap.normal_maps.insert(key_char, command);
+            keymap.edit_maps.insert(&keys, command.to_owned());
+            keymap.normal_maps.insert(&keys, command);
         },
 
         AppCommand::EditMoveItem(_) | AppCommand::EditGotoTop |
@@ -96,11 +129,34 @@ fn insert_keybinding(
         AppCommand::EditNew(_) | AppCommand::EditListScroll(_) |
         AppCommand::QuitEdit =>
         {
-            app.keymap.edit_maps.insert(key_char, command);
+            keymap.edit_maps.insert(&keys, command);
+        },
+
+        AppCommand::HideCompletion | AppCommand::CursorMove(..) |
+        AppCommand::SelectCandidate(_) | AppCommand::CommandScroll(_) |
+        AppCommand::HistorySearch =>
+        {
+            keymap.input_maps.insert(&keys, command);
+        },
+
+        // Search mode can be switched when inputting search.
+        AppCommand::SearchMode(_) => {
+            keymap.normal_maps.insert(&keys, command.to_owned());
+            keymap.input_maps.insert(&keys, command);
+        },
+
+        // Confirm works in any mode.
+        AppCommand::Confirm => {
+            keymap.navi_maps.insert(&keys, command.to_owned());
+            keymap.edit_maps.insert(&keys, command.to_owned());
+            keymap.normal_maps.insert(&keys, command.to_owned());
+            keymap.input_maps.insert(&keys, command);
         },
 
         _ => {
-            app.keymap.normal_maps.insert(key_char, command);
+            keymap.normal_maps.insert(&keys, command);
         }
     }
+
+    Ok(())
 }
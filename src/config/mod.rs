@@ -2,6 +2,7 @@
 
 mod types;
 mod keymap;
+mod key_sequence;
 
 use std::{
     fs::{self, File, OpenOptions},
@@ -13,10 +14,11 @@ use toml_edit::DocumentMut;
 
 use keymap::init_keymap;
 
-use crate::{app::App, error::{AppError, AppResult}};
+use crate::{app::App, command::CommandHistory, error::{AppError, AppResult}};
 
 pub use types::*;
-pub use keymap::Keymap;
+pub use keymap::{Keymap, KeymapMode};
+pub use key_sequence::Key;
 
 /// Get T from Option<T>.
 /// When it succeeded, return the value, otherwise throw anyhow error.
@@ -48,6 +50,10 @@ pub fn init_config(app: &mut App) -> AppResult<()> {
         errors.append_errors(err.iter());
     }
 
+    if let Err(err) = init_history(app) {
+        errors.append_errors(err.iter());
+    }
+
     if !errors.is_empty() {
         return Err(errors)
     }
@@ -67,6 +73,14 @@ fn init_auto_config(app: &mut App, path: String) -> AppResult<()> {
         errors.add_error(err);
     }
 
+    if let Err(err) = crate::key_event::sort_read_config(app, &document) {
+        errors.add_error(err);
+    }
+
+    if let Err(err) = crate::key_event::macro_read_config(app, &document) {
+        errors.add_error(err);
+    }
+
     if !errors.is_empty() {
         return Err(errors)
     }
@@ -76,7 +90,8 @@ fn init_auto_config(app: &mut App, path: String) -> AppResult<()> {
 
 fn init_user_config(app: &mut App, path: String) -> AppResult<()> {
     let configs = [
-        "default_shell", "gui_commands", "file_read_program"
+        "default_shell", "gui_commands", "file_read_program", "highlight_theme",
+        "previewers", "open_rules", "history_size", "ignore_patterns", "columns"
     ];
     let mut errors = AppError::new();
 
@@ -95,6 +110,22 @@ fn init_user_config(app: &mut App, path: String) -> AppResult<()> {
         app.config.push(default);
     }
 
+    let previewers = match Config::get_value(&app.config, "previewers") {
+        ConfigValue::Table(previewers) => previewers.as_slice(),
+        _ => &[]
+    };
+    if let Err(err) = app.previewer.set_rules(previewers) {
+        errors.add_error(err);
+    }
+
+    let ignore_patterns = match Config::get_value(&app.config, "ignore_patterns") {
+        ConfigValue::Vec(patterns) => patterns.as_slice(),
+        _ => &[]
+    };
+    if let Err(err) = app.ignore_rules.set_patterns(ignore_patterns) {
+        errors.add_error(err);
+    }
+
     if document.len() > configs.len() {
         errors.add_error(anyhow::anyhow!(
             "There're useless config in user_config.toml"
@@ -108,6 +139,17 @@ fn init_user_config(app: &mut App, path: String) -> AppResult<()> {
     Ok(())
 }
 
+fn init_history(app: &mut App) -> AppResult<()> {
+    let limit = match Config::get_value(&app.config, "history_size") {
+        ConfigValue::Number(limit) => *limit,
+        _ => panic!("Unknow error occurred at init_history fn in config/mod.rs")
+    };
+
+    app.command_history = CommandHistory::load(get_history_file()?, limit)?;
+
+    Ok(())
+}
+
 /// Write modified document into auto_config file.
 pub fn write_document(document: DocumentMut) -> io::Result<()> {
     let (path, _, _) = get_conf_file()?;
@@ -144,6 +186,26 @@ pub fn get_document(path: String) -> Result<DocumentMut> {
 /// Get the two config file and create them if they don't exist.
 /// Format: (auto_config_path, user_config_path)
 pub fn get_conf_file() -> io::Result<(String, String, String)> {
+    let config_dir = get_conf_dir()?;
+
+    Ok((
+        format!("{}auto_config.toml", config_dir),
+        format!("{}user_config.toml", config_dir),
+        format!("{}keymap.toml", config_dir)
+        // Dev
+        // format!("{}auto_config_dev.toml", config_dir),
+        // format!("{}user_config_dev.toml", config_dir),
+        // format!("{}keymap_dev.toml", config_dir)
+    ))
+}
+
+/// Get the file storing history of command line.
+pub fn get_history_file() -> io::Result<String> {
+    Ok(format!("{}history.toml", get_conf_dir()?))
+}
+
+/// Get the config directory and create it if it doesn't exist.
+fn get_conf_dir() -> io::Result<String> {
     let user = std::env::var("USER").expect("Failed to get user name!");
     let config_dir = format!(
         "{}/.config/springhan/hire/",
@@ -162,13 +224,5 @@ pub fn get_conf_file() -> io::Result<(String, String, String)> {
         }
     }
 
-    Ok((
-        format!("{}auto_config.toml", config_dir),
-        format!("{}user_config.toml", config_dir),
-        format!("{}keymap.toml", config_dir)
-        // Dev
-        // format!("{}auto_config_dev.toml", config_dir),
-        // format!("{}user_config_dev.toml", config_dir),
-        // format!("{}keymap_dev.toml", config_dir)
-    ))
+    Ok(config_dir)
 }
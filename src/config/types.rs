@@ -2,21 +2,70 @@
 
 use std::borrow::Cow;
 
-use toml_edit::Item;
-use anyhow::{bail, Result};
+use toml_edit::{Item, Table};
+use anyhow::{anyhow, bail, Result};
 
 use crate::option_get;
+use crate::utils::FilePattern;
 
 pub type AppConfig<'a> = Vec<Config<'a>>;
 
 #[derive(Clone)]
 pub enum ConfigValue<'a> {
     // Bool(bool),
+    Number(usize),
     String(Cow<'a, str>),
     Vec(Vec<Cow<'a, str>>),
+    /// Pairs of key & value, keeping the order in config file.
+    Table(Vec<(Cow<'a, str>, Cow<'a, str>)>),
+    OpenRules(Vec<OpenRule>),
+    Columns(Vec<Column>),
     // HashMap(HashMap<char, String>),
 }
 
+/// The rule to open files, read from `open_rules` in user_config.toml.
+#[derive(Clone)]
+pub struct OpenRule {
+    pub name: String,
+
+    /// The `$.` in command is replaced with the path of file.
+    pub command: String,
+
+    /// GUI programs run in background, without leaving hire.
+    pub gui: bool,
+
+    /// Whether to refresh file list after the program exits.
+    pub refresh: bool,
+
+    /// The rule matches all files when there's no pattern.
+    pub patterns: Vec<FilePattern>,
+}
+
+/// The info shown at the right side of files in current block, read from `columns` in user_config.toml.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Column {
+    /// Human readable size, a directory has its total size once computed.
+    Size,
+
+    /// Last modified time with the format of strftime.
+    Modified(String),
+
+    /// Permissions like `drwxr-xr-x`.
+    Permissions,
+
+    /// Permissions in octal like `755`.
+    Octal,
+
+    /// Format: user:group
+    Owner,
+
+    /// The target of symlink.
+    Link,
+
+    /// The number of entries in directory.
+    Count,
+}
+
 pub struct Config<'a> {
     name: String,
     value: ConfigValue<'a>
@@ -29,6 +78,12 @@ impl<'a> Config<'a> {
             "gui_commands" => ConfigValue::Vec(Vec::new()),
             "default_shell" => ConfigValue::String(Cow::Borrowed("bash")),
             "file_read_program" => ConfigValue::String(Cow::Borrowed("vim")),
+            "highlight_theme" => ConfigValue::String(Cow::Borrowed("base16-ocean.dark")),
+            "previewers" => ConfigValue::Table(Vec::new()),
+            "open_rules" => ConfigValue::OpenRules(Vec::new()),
+            "history_size" => ConfigValue::Number(1000),
+            "ignore_patterns" => ConfigValue::Vec(Vec::new()),
+            "columns" => ConfigValue::Columns(Vec::new()),
             _ => panic!("Unknow error occurred at default_value fn in types.rs.")
         }
     }
@@ -56,9 +111,14 @@ impl<'a> Config<'a> {
         let err_msg = format!("The type of config property {} is error", self.name);
 
         match self.name.as_str() {
-            "default_shell" | "file_read_program" =>
+            "default_shell" | "file_read_program" | "highlight_theme" =>
                 self.value = Self::get_str(value, err_msg)?,
 
+            "history_size" => {
+                let number = option_get!(value.as_integer(), err_msg);
+                self.value = ConfigValue::Number(usize::try_from(number)?);
+            },
+
             "gui_commands" => {
                 let _value = value.as_array();
                 if _value.is_none() {
@@ -80,6 +140,64 @@ impl<'a> Config<'a> {
                 }
             },
 
+            "ignore_patterns" => {
+                let array = option_get!(value.as_array(), err_msg);
+
+                if let ConfigValue::Vec(ref mut patterns) = self.value {
+                    for pattern in array.iter() {
+                        let pattern = option_get!(
+                            pattern.as_str(),
+                            "Meet type error when setting ignore_patterns"
+                        );
+
+                        patterns.push(Cow::Owned(pattern.to_owned()));
+                    }
+                }
+            },
+
+            "columns" => {
+                let array = option_get!(value.as_array(), err_msg);
+
+                if let ConfigValue::Columns(ref mut columns) = self.value {
+                    for column in array.iter() {
+                        let column = option_get!(
+                            column.as_str(),
+                            "Meet type error when setting columns"
+                        );
+
+                        columns.push(Column::from_str(column)?);
+                    }
+                }
+            },
+
+            "previewers" => {
+                let table = option_get!(value.as_table_like(), err_msg);
+
+                if let ConfigValue::Table(ref mut previewers) = self.value {
+                    for (pattern, command) in table.iter() {
+                        let command = option_get!(
+                            command.as_str(),
+                            "Meet type error when setting previewers"
+                        );
+
+                        previewers.push((
+                            Cow::Owned(pattern.to_owned()),
+                            Cow::Owned(command.to_owned())
+                        ));
+                    }
+                }
+            },
+
+            "open_rules" => {
+                let tables = option_get!(value.as_array_of_tables(), err_msg);
+
+                if let ConfigValue::OpenRules(ref mut rules) = self.value {
+                    for table in tables.iter() {
+                        rules.push(OpenRule::from_table(table)?);
+                    }
+                }
+            },
+
             _ => panic!("Unknow error occurred at value_from fn in types.rs.")
         }
 
@@ -92,3 +210,95 @@ impl<'a> Config<'a> {
         Ok(ConfigValue::String(Cow::Owned(_str.to_owned())))
     }
 }
+
+impl OpenRule {
+    pub fn is_match(&self, path: &std::path::Path) -> bool {
+        self.patterns.is_empty() ||
+            self.patterns.iter().any(|pattern| pattern.is_match(path))
+    }
+
+    fn from_table(table: &Table) -> Result<Self> {
+        let err_msg = "Meet type error when setting open_rules";
+
+        let command = option_get!(
+            table.get("command").and_then(|item| item.as_str()),
+            "The command of open rule is missing"
+        ).to_owned();
+
+        // Use the program as name by default.
+        let name = match table.get("name") {
+            Some(item) => option_get!(item.as_str(), err_msg).to_owned(),
+            None => command.split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_owned()
+        };
+
+        let get_bool = |key: &str| -> Result<bool> {
+            match table.get(key) {
+                Some(item) => item.as_bool().ok_or(anyhow!("{}", err_msg)),
+                None => Ok(false)
+            }
+        };
+
+        let mut patterns: Vec<FilePattern> = Vec::new();
+        for key in ["extensions", "mime_types", "globs"] {
+            let values = match table.get(key) {
+                Some(item) => option_get!(item.as_array(), err_msg),
+                None => continue
+            };
+
+            for value in values.iter() {
+                let value = option_get!(value.as_str(), err_msg);
+                patterns.push(match key {
+                    "extensions" => FilePattern::extension(value),
+                    "mime_types" => FilePattern::Mime(value.to_owned()),
+                    _ => FilePattern::glob(value)?
+                });
+            }
+        }
+
+        Ok(Self {
+            name,
+            command,
+            patterns,
+            gui: get_bool("gui")?,
+            refresh: get_bool("refresh")?,
+        })
+    }
+}
+
+impl Column {
+    /// The format of `modified` column without specific one.
+    const TIME_FORMAT: &'static str = "%Y-%m-%d %H:%M";
+
+    /// Parse column from name, `modified` accepts a format like `modified:%m-%d %H:%M`.
+    pub fn from_str(value: &str) -> Result<Self> {
+        let column = match value.split_once(':') {
+            Some(("modified", format)) => {
+                let invalid = chrono::format::StrftimeItems::new(format)
+                    .any(|item| item == chrono::format::Item::Error);
+                if invalid {
+                    bail!("Invalid time format of column: {}", value)
+                }
+
+                Self::Modified(format.to_owned())
+            },
+
+            Some(_) => bail!("Unknow column: {}", value),
+
+            None => match value {
+                "size"        => Self::Size,
+                "modified"    => Self::Modified(Self::TIME_FORMAT.to_owned()),
+                "permissions" => Self::Permissions,
+                "octal"       => Self::Octal,
+                "owner"       => Self::Owner,
+                "link"        => Self::Link,
+                "count"       => Self::Count,
+                _ => bail!("Unknow column: {}", value)
+            }
+        };
+
+        Ok(column)
+    }
+}
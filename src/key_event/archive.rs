@@ -0,0 +1,821 @@
+// Archive Browsing
+
+use std::env;
+use std::mem::take;
+use std::process;
+use std::sync::OnceLock;
+use std::fs::{self, DirBuilder, File, OpenOptions, Permissions};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::fs::{symlink, DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, NaiveDate};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use super::switch::SwitchStruct;
+use super::{FsOperation, SwitchCase, SwitchCaseData};
+use super::job::{JobContext, JobKind, JobResult};
+use super::paste_operation::{copy_stream, item_size};
+
+use crate::rt_error;
+use crate::app::{App, FileSaver};
+use crate::utils::{CmdContent, MarkedFiles};
+use crate::error::{AppError, AppResult, ErrorType};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+/// An entry inside archive.
+#[derive(Clone)]
+pub struct ArchiveEntry {
+    /// The path relative to the root of archive.
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    pub mode: Option<u32>,
+    pub modified: Option<SystemTime>,
+
+    /// The target of symbolic link.
+    pub link: Option<PathBuf>,
+}
+
+/// The entries of the last browsed archive, to avoid reading it for each movement.
+#[derive(Default)]
+pub struct ArchiveCache {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    entries: Vec<ArchiveEntry>,
+}
+
+#[derive(Clone)]
+struct CompressState {
+    target: PathBuf,
+}
+
+impl SwitchStruct for CompressState {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Tar   => "tar",
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::Zip   => "zip",
+        }
+    }
+}
+
+impl ArchiveCache {
+    /// List the entries right under INNER of ARCHIVE.
+    pub fn list(&mut self, archive: &Path, inner: &Path) -> io::Result<Vec<FileSaver>> {
+        let modified = fs::metadata(archive)?.modified().ok();
+        if self.path != archive || self.modified != modified {
+            let mut entries: Vec<ArchiveEntry> = Vec::new();
+            for_each_entry(archive, |entry, _| {
+                entries.push(entry);
+                Ok(true)
+            })?;
+
+            *self = Self { path: archive.to_path_buf(), modified, entries };
+        }
+
+        let mut names: HashSet<String> = HashSet::new();
+        let mut files: Vec<FileSaver> = Vec::new();
+
+        for entry in self.entries.iter() {
+            let mut components = match entry.path.strip_prefix(inner) {
+                Ok(rest) => rest.components(),
+                Err(_) => continue
+            };
+            let name = match components.next() {
+                Some(name) => name.as_os_str().to_string_lossy().to_string(),
+                None => continue
+            };
+
+            if !names.insert(name.to_owned()) {
+                continue;
+            }
+
+            // The parent directories of entries may be omitted in archive.
+            if components.next().is_some() {
+                files.push(FileSaver::archive_entry(name, true, 0, 0o755, None));
+                continue;
+            }
+
+            let default_mode = if entry.is_dir { 0o755 } else { 0o644 };
+            files.push(FileSaver::archive_entry(
+                name,
+                entry.is_dir,
+                entry.size,
+                entry.mode.unwrap_or(default_mode) & 0o7777,
+                entry.modified
+            ));
+        }
+
+        Ok(files)
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveFormat::from_path(path).is_some() && path.is_file()
+}
+
+/// Split PATH into the archive file and the path inside it.
+/// The inner path is empty when PATH is the archive itself.
+pub fn split_archive_path(path: &Path) -> Option<(PathBuf, PathBuf)> {
+    for ancestor in path.ancestors() {
+        let Ok(metadata) = fs::metadata(ancestor) else {
+            continue;
+        };
+
+        if !metadata.is_file() || ArchiveFormat::from_path(ancestor).is_none() {
+            return None
+        }
+
+        let inner = path.strip_prefix(ancestor).ok()?;
+        return Some((ancestor.to_path_buf(), inner.to_path_buf()))
+    }
+
+    None
+}
+
+/// Get the archive & inner path when PATH is an entry inside archive.
+pub fn archive_entry_path(path: &Path) -> Option<(PathBuf, PathBuf)> {
+    split_archive_path(path).filter(|(_, inner)| !inner.as_os_str().is_empty())
+}
+
+/// Read at most LIMIT bytes of the file at INNER of ARCHIVE.
+pub fn read_entry(archive: &Path, inner: &Path, limit: u64) -> io::Result<Vec<u8>> {
+    let mut content: Option<Vec<u8>> = None;
+
+    for_each_entry(archive, |entry, reader| {
+        if entry.path != inner || entry.is_dir {
+            return Ok(true)
+        }
+
+        let mut bytes: Vec<u8> = Vec::new();
+        (&mut *reader).take(limit).read_to_end(&mut bytes)?;
+        content = Some(bytes);
+
+        Ok(false)
+    })?;
+
+    content.ok_or(io::Error::new(
+        ErrorKind::NotFound,
+        format!("Cannot find {} in archive", inner.to_string_lossy())
+    ))
+}
+
+/// Extract INNER of ARCHIVE to TARGET, directories are extracted with their content.
+pub fn extract_entry(archive: &Path,
+                     inner: &Path,
+                     target: &Path,
+                     context: &mut JobContext
+) -> io::Result<()>
+{
+    let mut found = false;
+
+    for_each_entry(archive, |entry, reader| {
+        if context.cancelled() {
+            return Err(io::Error::new(ErrorKind::Interrupted, "The job is cancelled"))
+        }
+
+        // A single file is extracted to TARGET itself.
+        // Files are only written inside ROOT, REST is the path relative to it.
+        let (root, rest) = match entry.path.strip_prefix(inner) {
+            Ok(rest) if rest.as_os_str().is_empty() => (
+                target.parent().unwrap_or(Path::new("/")),
+                PathBuf::from(target.file_name().unwrap_or_default())
+            ),
+            Ok(rest) => (target, rest.to_path_buf()),
+            Err(_) => return Ok(true)
+        };
+        let path = root.join(&rest);
+        found = true;
+
+        check_symlinks(root, &rest)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if entry.is_dir {
+            fs::create_dir_all(&path)?;
+        } else if let Some(ref link) = entry.link {
+            if !link_inside(rest.parent().unwrap_or(Path::new("")), link) {
+                return Err(io::Error::new(ErrorKind::InvalidData, format!(
+                    "The symbolic link {} points outside of the extracted directory",
+                    entry.path.to_string_lossy()
+                )))
+            }
+
+            symlink(link, &path)?;
+        } else {
+            let mut writer = File::create(&path)?;
+            copy_stream(reader, &mut writer, context)?;
+
+            if let Some(mode) = entry.mode {
+                fs::set_permissions(&path, Permissions::from_mode(mode & 0o7777))?;
+            }
+        }
+
+        context.add_progress(1, 0);
+        Ok(true)
+    })?;
+
+    if !found {
+        return Err(io::Error::new(
+            ErrorKind::NotFound,
+            format!("Cannot find {} in archive", inner.to_string_lossy())
+        ))
+    }
+
+    Ok(())
+}
+
+/// Refuse to write REST in ROOT through symbolic links created by previous entries,
+/// which could lead to files outside of ROOT.
+fn check_symlinks(root: &Path, rest: &Path) -> io::Result<()> {
+    let mut path = root.to_path_buf();
+
+    for component in rest.components() {
+        path.push(component);
+
+        let is_symlink = path.symlink_metadata()
+            .is_ok_and(|metadata| metadata.is_symlink());
+        if is_symlink {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!(
+                "Refuse to extract through the symbolic link {}",
+                path.to_string_lossy()
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether LINK in the directory DIR, both relative to the extracted root, stays inside the root.
+fn link_inside(dir: &Path, link: &Path) -> bool {
+    let mut depth = dir.components().count();
+
+    for component in link.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => (),
+            Component::ParentDir if depth > 0 => depth -= 1,
+            _ => return false
+        }
+    }
+
+    true
+}
+
+/// Get the number of files & bytes of INNER in ARCHIVE.
+pub fn entry_size(archive: &Path, inner: &Path) -> (usize, u64) {
+    let (mut files, mut bytes) = (0, 0);
+    let _ = for_each_entry(archive, |entry, _| {
+        if entry.path.starts_with(inner) {
+            files += 1;
+            bytes += entry.size;
+        }
+
+        Ok(true)
+    });
+
+    (files, bytes)
+}
+
+/// Files inside archive are extracted to a temporary directory to be opened by other programs.
+/// Other files are returned directly.
+pub fn extract_if_archived(path: &Path) -> io::Result<PathBuf> {
+    let (archive, inner) = if let Some(paths) = archive_entry_path(path) {
+        paths
+    } else {
+        return Ok(path.to_path_buf())
+    };
+
+    let archive_name = archive.file_name().unwrap_or_default();
+    let target = private_dir()?
+        .join(archive_name)
+        .join(&inner);
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // The file extracted before may be opened by other programs, so a new one is created.
+    if target.symlink_metadata().is_ok() {
+        fs::remove_file(&target)?;
+    }
+
+    let mut found = false;
+    for_each_entry(&archive, |entry, reader| {
+        if entry.path != inner || entry.is_dir {
+            return Ok(true)
+        }
+
+        let mode = entry.mode.map_or(0o600, |mode| mode & 0o777);
+        let mut writer = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(mode)
+            .open(&target)?;
+        io::copy(reader, &mut writer)?;
+
+        // The mode of created file is masked by umask.
+        fs::set_permissions(&target, Permissions::from_mode(mode))?;
+        found = true;
+
+        Ok(false)
+    })?;
+
+    if !found {
+        return Err(io::Error::new(
+            ErrorKind::NotFound,
+            format!("Cannot find {} in archive", inner.to_string_lossy())
+        ))
+    }
+
+    Ok(target)
+}
+
+/// The directory of current process to extract files, which is only accessible by the user.
+fn private_dir() -> io::Result<&'static Path> {
+    static PRIVATE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+    if let Some(dir) = PRIVATE_DIR.get() {
+        return Ok(dir)
+    }
+
+    // The directory must be created by us, rather than one planted by others.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    for idx in 0..100 {
+        let dir = env::temp_dir().join(format!("hire-archive-{}-{}", process::id(), nanos + idx));
+
+        match DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(_) => return Ok(PRIVATE_DIR.get_or_init(|| dir)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err)
+        }
+    }
+
+    Err(io::Error::new(ErrorKind::AlreadyExists, "Cannot create temporary directory"))
+}
+
+/// Call FUNC with each entry of ARCHIVE and its content, stop when FUNC returns false.
+fn for_each_entry<F>(archive: &Path, mut func: F) -> io::Result<()>
+where F: FnMut(ArchiveEntry, &mut dyn Read) -> io::Result<bool>
+{
+    let format = if let Some(format) = ArchiveFormat::from_path(archive) {
+        format
+    } else {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "Unsupported archive format"))
+    };
+    let file = File::open(archive)?;
+
+    if format == ArchiveFormat::Zip {
+        let mut zip = ZipArchive::new(file)?;
+
+        for idx in 0..zip.len() {
+            let mut zip_file = zip.by_index(idx)?;
+            let path = match zip_file.enclosed_name().as_deref().and_then(normalize_path) {
+                Some(path) => path,
+                None => continue
+            };
+
+            // The content of symbolic link is its target.
+            let link = if zip_file.is_symlink() {
+                let mut link = String::new();
+                zip_file.read_to_string(&mut link)?;
+                Some(PathBuf::from(link))
+            } else {
+                None
+            };
+
+            let entry = ArchiveEntry {
+                path,
+                link,
+                is_dir: zip_file.is_dir(),
+                size: zip_file.size(),
+                mode: zip_file.unix_mode(),
+                modified: zip_file.last_modified().and_then(zip_time),
+            };
+
+            if !func(entry, &mut zip_file)? {
+                break;
+            }
+        }
+
+        return Ok(())
+    }
+
+    let reader: Box<dyn Read> = if format == ArchiveFormat::TarGz {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let mut tar = tar::Archive::new(reader);
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+        if !(entry_type.is_file() || entry_type.is_dir() || entry_type.is_symlink()) {
+            continue;
+        }
+
+        let path = match normalize_path(&entry.path()?) {
+            Some(path) => path,
+            None => continue
+        };
+
+        let link = if entry_type.is_symlink() {
+            entry.link_name()?.map(|link| link.into_owned())
+        } else {
+            None
+        };
+
+        let header = entry.header();
+        let entry_info = ArchiveEntry {
+            path,
+            link,
+            is_dir: entry_type.is_dir(),
+            size: header.size()?,
+            mode: header.mode().ok(),
+            modified: header.mtime()
+                .ok()
+                .map(|time| UNIX_EPOCH + Duration::from_secs(time)),
+        };
+
+        if !func(entry_info, &mut entry)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove "./" & trailing slashes, unsafe paths such as "../file" are ignored.
+fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => result.push(part),
+            Component::CurDir => (),
+            _ => return None
+        }
+    }
+
+    if result.as_os_str().is_empty() {
+        return None
+    }
+
+    Some(result)
+}
+
+fn zip_time(time: zip::DateTime) -> Option<SystemTime> {
+    let datetime = NaiveDate::from_ymd_opt(
+        time.year() as i32,
+        time.month() as u32,
+        time.day() as u32
+    )?
+        .and_hms_opt(time.hour() as u32, time.minute() as u32, time.second() as u32)?
+        .and_local_timezone(Local)
+        .single()?;
+
+    Some(datetime.into())
+}
+
+/// Pack the marked files into archive NAME, whose format is decided by its extension.
+/// When the extension is unknown, ask the user to choose one.
+pub fn compress(app: &mut App, name: &str) -> AppResult<()> {
+    if app.marked_files.is_empty() {
+        return Err(ErrorType::NoSelected.pack())
+    }
+
+    let target = app.current_path().join(name);
+    if let Some(format) = ArchiveFormat::from_path(&target) {
+        return compress_job(app, target, format)
+    }
+
+    SwitchCase::new(
+        app,
+        compress_switch,
+        false,
+        CmdContent::String(String::from(
+            "Choose the archive format: [t] tar  [g] tar.gz  [z] zip"
+        )),
+        SwitchCaseData::Struct(Box::new(CompressState { target }))
+    );
+
+    Ok(())
+}
+
+fn compress_switch(app: &mut App, key: char, data: SwitchCaseData) -> AppResult<bool> {
+    let state = if let SwitchCaseData::Struct(data) = data {
+        match data.as_any().downcast_ref::<CompressState>() {
+            Some(state) => state.to_owned(),
+            None => panic!("Unknow panic occurred at compress_switch in archive.rs!"),
+        }
+    } else {
+        panic!("Unexpected situation at compress_switch in archive.rs.")
+    };
+
+    let format = match key {
+        't' => ArchiveFormat::Tar,
+        'g' => ArchiveFormat::TarGz,
+        'z' => ArchiveFormat::Zip,
+        _ => return Ok(true)
+    };
+
+    let mut target = state.target.into_os_string();
+    target.push(format!(".{}", format.extension()));
+    compress_job(app, PathBuf::from(target), format)?;
+
+    Ok(true)
+}
+
+fn compress_job(app: &mut App, target: PathBuf, format: ArchiveFormat) -> AppResult<()> {
+    if target.symlink_metadata().is_ok() {
+        rt_error!(format!("{} already exists", target.to_string_lossy()))
+    }
+
+    let files = take(&mut app.marked_files);
+    app.mark_expand = false;
+
+    app.spawn_job(JobKind::Compress, target.to_owned(), move |context| {
+        let mut errors = AppError::new();
+        let mut operations: Vec<FsOperation> = Vec::new();
+
+        match write_archive(&files, &target, format, context) {
+            Ok(_) => operations.push(FsOperation::Created(target)),
+            Err(err) => {
+                // Remove the incomplete archive.
+                let _ = fs::remove_file(&target);
+
+                if err.kind() == ErrorKind::Interrupted {
+                    errors.add_error(anyhow::anyhow!(
+                        "Compressing to {} is cancelled",
+                        target.to_string_lossy()
+                    ));
+                } else {
+                    errors.add_error(err);
+                }
+            }
+        }
+
+        JobResult { errors, operations }
+    })
+}
+
+fn write_archive(files: &HashMap<PathBuf, MarkedFiles>,
+                 target: &Path,
+                 format: ArchiveFormat,
+                 context: &mut JobContext
+) -> io::Result<()>
+{
+    let (mut total_files, mut total_bytes) = (0, 0);
+    for (path, files) in files.iter() {
+        for file in files.files.keys() {
+            let (_files, _bytes) = item_size(&path.join(file));
+            total_files += _files;
+            total_bytes += _bytes;
+        }
+    }
+    context.set_total(total_files, total_bytes);
+
+    let file = File::create_new(target)?;
+    let items = files.iter().flat_map(|(path, files)| {
+        files.files.keys().map(|name| (path.join(name), PathBuf::from(name)))
+    });
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = ZipWriter::new(file);
+            for (path, name) in items {
+                append_zip(&mut zip, &path, &name, context)?;
+            }
+            zip.finish()?;
+        },
+
+        ArchiveFormat::Tar => {
+            write_tar(file, items, context)?;
+        },
+
+        ArchiveFormat::TarGz => {
+            write_tar(GzEncoder::new(file, Compression::default()), items, context)?.finish()?;
+        },
+    }
+
+    Ok(())
+}
+
+fn write_tar<W, I>(writer: W, items: I, context: &mut JobContext) -> io::Result<W>
+where
+    W: Write,
+    I: Iterator<Item = (PathBuf, PathBuf)>
+{
+    let mut builder = tar::Builder::new(writer);
+    builder.follow_symlinks(false);
+
+    for (path, name) in items {
+        append_tar(&mut builder, &path, &name, context)?;
+    }
+
+    builder.into_inner()
+}
+
+fn append_tar<W: Write>(builder: &mut tar::Builder<W>,
+                        path: &Path,
+                        name: &Path,
+                        context: &mut JobContext
+) -> io::Result<()>
+{
+    if context.cancelled() {
+        return Err(io::Error::new(ErrorKind::Interrupted, "The job is cancelled"))
+    }
+
+    let metadata = fs::symlink_metadata(path)?;
+    builder.append_path_with_name(path, name)?;
+    context.add_progress(1, if metadata.is_file() { metadata.len() } else { 0 });
+
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            append_tar(builder, &entry.path(), &name.join(entry.file_name()), context)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn append_zip(zip: &mut ZipWriter<File>,
+              path: &Path,
+              name: &Path,
+              context: &mut JobContext
+) -> io::Result<()>
+{
+    let metadata = fs::symlink_metadata(path)?;
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .unix_permissions(metadata.permissions().mode() & 0o777)
+        .large_file(metadata.len() >= u32::MAX as u64);
+    let name_str = name.to_string_lossy();
+
+    if metadata.is_symlink() {
+        zip.add_symlink(name_str, fs::read_link(path)?.to_string_lossy(), options)?;
+    } else if metadata.is_dir() {
+        zip.add_directory(name_str, options)?;
+
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            append_zip(zip, &entry.path(), &name.join(entry.file_name()), context)?;
+        }
+    } else {
+        zip.start_file(name_str, options)?;
+        copy_stream(&mut File::open(path)?, zip, context)?;
+    }
+
+    context.add_progress(1, 0);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path(Path::new("./a/b/")), Some(PathBuf::from("a/b")));
+        assert_eq!(normalize_path(Path::new("../a")), None);
+        assert_eq!(normalize_path(Path::new("/etc/passwd")), None);
+        assert_eq!(normalize_path(Path::new("./")), None);
+    }
+
+    #[test]
+    fn test_archive_format() {
+        assert!(ArchiveFormat::from_path(Path::new("a.TAR.GZ")) == Some(ArchiveFormat::TarGz));
+        assert!(ArchiveFormat::from_path(Path::new("a.tgz")) == Some(ArchiveFormat::TarGz));
+        assert!(ArchiveFormat::from_path(Path::new("a.tar")) == Some(ArchiveFormat::Tar));
+        assert!(ArchiveFormat::from_path(Path::new("a.zip")) == Some(ArchiveFormat::Zip));
+        assert!(ArchiveFormat::from_path(Path::new("a.gz")).is_none());
+    }
+
+    #[test]
+    fn test_extract_entry() {
+        use std::sync::{Arc, atomic::AtomicBool, mpsc};
+
+        let root = TestDir::new("extract");
+
+        let archive = root.join("test.tar");
+        let mut builder = tar::Builder::new(File::create(&archive).unwrap());
+        for (path, content) in [("dir/a.txt", "alpha"), ("dir/sub/b.txt", "beta")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o640);
+            header.set_cksum();
+            builder.append_data(&mut header, path, content.as_bytes()).unwrap();
+        }
+        builder.finish().unwrap();
+        drop(builder);
+
+        let (sender, _receiver) = mpsc::channel();
+        let mut context = JobContext::new(0, sender, Arc::new(AtomicBool::new(false)));
+
+        // Single file
+        let target = root.join("a.txt");
+        extract_entry(&archive, Path::new("dir/a.txt"), &target, &mut context).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "alpha");
+        assert_eq!(fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o640);
+
+        // Directory
+        let target = root.join("extracted");
+        extract_entry(&archive, Path::new("dir"), &target, &mut context).unwrap();
+        assert_eq!(fs::read_to_string(target.join("a.txt")).unwrap(), "alpha");
+        assert_eq!(fs::read_to_string(target.join("sub/b.txt")).unwrap(), "beta");
+
+        // Files opened by other programs
+        let path = archive.join("dir/sub/b.txt");
+        let extracted = extract_if_archived(&path).unwrap();
+        assert_eq!(fs::read_to_string(&extracted).unwrap(), "beta");
+        assert_eq!(fs::metadata(&extracted).unwrap().permissions().mode() & 0o777, 0o640);
+        assert_eq!(fs::metadata(private_dir().unwrap()).unwrap().permissions().mode() & 0o777, 0o700);
+        assert_eq!(extract_if_archived(&path).unwrap(), extracted);
+    }
+
+    #[test]
+    fn test_extract_symlink() {
+        use std::sync::{Arc, atomic::AtomicBool, mpsc};
+
+        let root = TestDir::new("extract_symlink");
+        fs::create_dir_all(root.join("outside")).unwrap();
+
+        // A symbolic link followed by a file written through it.
+        let write_archive = |name: &str, link: &Path| {
+            let archive = root.join(name);
+            let mut builder = tar::Builder::new(File::create(&archive).unwrap());
+
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            header.set_link_name(link).unwrap();
+            header.set_cksum();
+            builder.append_data(&mut header, "dir/evil", io::empty()).unwrap();
+
+            let mut header = tar::Header::new_gnu();
+            header.set_size(5);
+            header.set_cksum();
+            builder.append_data(&mut header, "dir/evil/pwned", "pwned".as_bytes()).unwrap();
+            builder.finish().unwrap();
+
+            archive
+        };
+
+        let (sender, _receiver) = mpsc::channel();
+        let mut context = JobContext::new(0, sender, Arc::new(AtomicBool::new(false)));
+
+        for (name, link) in [
+            ("absolute.tar", root.join("outside")),
+            ("relative.tar", PathBuf::from("../../outside")),
+            ("inside.tar", PathBuf::from(".")),
+        ] {
+            let archive = write_archive(name, &link);
+            let target = root.join(name).with_extension("extracted");
+            assert!(extract_entry(&archive, Path::new("dir"), &target, &mut context).is_err());
+            assert!(!root.join("outside/pwned").exists());
+            assert!(!target.join("pwned").exists());
+        }
+
+        assert!(link_inside(Path::new("a/b"), Path::new("../c")));
+        assert!(link_inside(Path::new("a"), Path::new("./../b/../c")));
+        assert!(!link_inside(Path::new(""), Path::new("../a")));
+        assert!(!link_inside(Path::new("a"), Path::new("/etc")));
+    }
+}
@@ -0,0 +1,462 @@
+// Bulk Rename
+
+use std::{env, fs, io, process};
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use regex::Regex;
+use ratatui::DefaultTerminal;
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span, Text};
+
+use super::shell::run_in_terminal;
+use super::switch::SwitchStruct;
+use super::{FsOperation, SwitchCase, SwitchCaseData};
+
+use crate::app::App;
+use crate::utils::CmdContent;
+use crate::error::{AppError, AppResult, ErrorType};
+use crate::{option_get, rt_error};
+
+/// Renames waiting for confirmation, only the changed files are kept.
+#[derive(Clone)]
+struct RenamePlan {
+    path: PathBuf,
+    renames: Vec<(String, String)>,
+}
+
+impl SwitchStruct for RenamePlan {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Rename the marked files of current directory (or the selected file) with OPERATION:
+/// `replace <regex> <replacement>`, `template <template>`, `case <upper|lower|title>` or `editor`.
+/// The result is previewed before applying.
+pub fn bulk_rename(
+    app: &mut App,
+    terminal: &mut DefaultTerminal,
+    operation: &str
+) -> AppResult<()>
+{
+    if app.root() {
+        rt_error!("Bulk rename cannot be used in the root directory")
+    }
+
+    let names = rename_targets(app)?;
+    let (operation, argument) = operation.split_once(' ')
+        .map_or((operation, ""), |(operation, argument)| (operation, argument.trim_start()));
+
+    let new_names = match operation {
+        "replace" => {
+            let (regex, replacement) = option_get!(
+                argument.split_once(' '),
+                "Usage: bulk_rename replace <regex> <replacement>"
+            );
+            let regex = Regex::new(regex).map_err(anyhow::Error::from)?;
+
+            names.iter()
+                .enumerate()
+                .map(|(idx, name)| {
+                    let replacement = expand_template(replacement, name, idx + 1)?;
+                    Ok(regex.replace_all(name, replacement.as_str()).into_owned())
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+        },
+
+        "template" => {
+            if argument.is_empty() {
+                rt_error!("Usage: bulk_rename template <template>")
+            }
+
+            names.iter()
+                .enumerate()
+                .map(|(idx, name)| expand_template(argument, name, idx + 1))
+                .collect::<anyhow::Result<Vec<_>>>()?
+        },
+
+        "case" => names.iter()
+            .map(|name| change_case(name, argument))
+            .collect::<anyhow::Result<Vec<_>>>()?,
+
+        "editor" => edit_names(app, terminal, &names)?,
+
+        _ => rt_error!(format!("Unknow operation of bulk rename: {}", operation))
+    };
+
+    preview(app, names, new_names);
+
+    Ok(())
+}
+
+/// Names of marked files in current directory ordered as they're shown,
+/// or the selected file when nothing is marked.
+fn rename_targets(app: &App) -> AppResult<Vec<String>> {
+    let names = match app.marked_files.get(&app.path) {
+        Some(marked) if !marked.files.is_empty() => app.current_files.iter()
+            .filter(|file| marked.files.contains_key(&file.name))
+            .map(|file| file.name.to_owned())
+            .collect(),
+
+        _ => match app.get_file_saver() {
+            Some(file) => vec![file.name.to_owned()],
+            None => return Err(ErrorType::NoSelected.pack())
+        }
+    };
+
+    Ok(names)
+}
+
+/// Expand placeholders of TEMPLATE for the file NAME, which is the IDX-th one.
+/// Placeholders: `{n}`, `{n:03}` (padded with zero), `{name}`, `{stem}` & `{ext}`.
+/// The `${...}` of regex replacement is kept as it is.
+fn expand_template(template: &str, name: &str, idx: usize) -> anyhow::Result<String> {
+    let (stem, ext) = split_extension(name);
+    let mut result = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if result.ends_with('$') {
+            result.push('{');
+            rest = &rest[1..];
+            continue;
+        }
+
+        let Some(end) = rest.find('}') else {
+            bail!("Unclosed placeholder in template: {}", template)
+        };
+
+        match &rest[1..end] {
+            "n"    => result.push_str(&idx.to_string()),
+            "name" => result.push_str(name),
+            "stem" => result.push_str(stem),
+            "ext"  => result.push_str(ext),
+
+            field => {
+                let width = field.strip_prefix("n:")
+                    .and_then(|width| width.parse::<usize>().ok());
+                match width {
+                    Some(width) => result.push_str(&format!("{:0width$}", idx)),
+                    None => bail!("Unknow placeholder in template: {{{}}}", field)
+                }
+            }
+        }
+
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+/// Split NAME into stem & extension, dotfiles have no extension.
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, ext),
+        _ => (name, "")
+    }
+}
+
+/// Change the case of stem of NAME with MODE, the extension is kept.
+fn change_case(name: &str, mode: &str) -> anyhow::Result<String> {
+    let (stem, ext) = split_extension(name);
+    let stem = match mode {
+        "upper" => stem.to_uppercase(),
+        "lower" => stem.to_lowercase(),
+
+        "title" => {
+            let mut title = String::new();
+            let mut word_start = true;
+            for c in stem.chars() {
+                if word_start {
+                    title.extend(c.to_uppercase());
+                } else {
+                    title.extend(c.to_lowercase());
+                }
+                word_start = !c.is_alphanumeric();
+            }
+
+            title
+        },
+
+        _ => bail!("Unknow case: {}, it could be upper, lower or title", mode)
+    };
+
+    if ext.is_empty() {
+        return Ok(stem)
+    }
+
+    Ok(format!("{}.{}", stem, ext))
+}
+
+/// Edit NAMES in a temporary file with `$EDITOR`, each line is the new name.
+fn edit_names(
+    app: &mut App,
+    terminal: &mut DefaultTerminal,
+    names: &[String]
+) -> AppResult<Vec<String>>
+{
+    // Each line is a name, the names with newline would shift the following ones.
+    if let Some(name) = names.iter().find(|name| name.contains('\n')) {
+        rt_error!(format!("Cannot rename {:?} in editor, its name contains newline", name))
+    }
+
+    let file = create_temp_file(names.join("\n") + "\n")?;
+
+    let editor = env::var("EDITOR").unwrap_or_else(|_| String::from("vi"));
+    let mut args = editor.split_whitespace();
+    let mut command = process::Command::new(option_get!(args.next(), "The $EDITOR is empty"));
+    command.args(args).arg(&file);
+
+    let result = run_in_terminal(app, terminal, command, false, false)
+        .and_then(|_| Ok(fs::read_to_string(&file)?));
+    let _ = fs::remove_file(&file);
+
+    let new_names = result?.lines()
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    if new_names.len() != names.len() {
+        rt_error!(format!(
+            "The number of names is changed from {} to {}, lines cannot be added or removed",
+            names.len(),
+            new_names.len()
+        ))
+    }
+
+    Ok(new_names)
+}
+
+/// Create a new temporary file only readable by current user, a pre-existing one is never used.
+fn create_temp_file(content: String) -> io::Result<PathBuf> {
+    for idx in 0..100 {
+        let file = env::temp_dir().join(format!("hire_rename_{}_{}.txt", process::id(), idx));
+        let result = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&file);
+
+        match result {
+            Ok(mut opened) => {
+                opened.write_all(content.as_bytes())?;
+                return Ok(file)
+            },
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err)
+        }
+    }
+
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, "Cannot create temporary file for renaming"))
+}
+
+/// Find the problem of each new name, which is None when it can be renamed.
+fn check_conflicts(
+    path: &Path,
+    renames: &[(String, String)]
+) -> Vec<Option<&'static str>>
+{
+    let sources = renames.iter()
+        .map(|(old, _)| old.as_str())
+        .collect::<HashSet<_>>();
+
+    let mut targets: HashSet<&str> = HashSet::new();
+    renames.iter()
+        .map(|(_, new)| {
+            if new.is_empty() || new == "." || new == ".." || new.contains('/') {
+                return Some("invalid name")
+            }
+
+            if !targets.insert(new.as_str()) {
+                return Some("duplicated")
+            }
+
+            // The files to be renamed will leave their names.
+            let exists = path.join(new).symlink_metadata().is_ok();
+            if exists && !sources.contains(new.as_str()) {
+                return Some("already exists")
+            }
+
+            None
+        })
+        .collect()
+}
+
+/// Show the renames, and apply them after confirmation if there's no conflict.
+fn preview(app: &mut App, names: Vec<String>, new_names: Vec<String>) {
+    let renames = names.into_iter()
+        .zip(new_names)
+        .filter(|(old, new)| old != new)
+        .collect::<Vec<_>>();
+
+    let path = app.path.to_owned();
+    let conflicts = check_conflicts(&path, &renames);
+    let conflict_count = conflicts.iter().flatten().count();
+
+    let mut text = Text::raw(if renames.is_empty() {
+        String::from("[Bulk Rename] Nothing is changed.")
+    } else if conflict_count > 0 {
+        format!("[Bulk Rename] {} conflicts, the files cannot be renamed.", conflict_count)
+    } else {
+        format!("[Bulk Rename] Rename {} files? (y to confirm)", renames.len())
+    });
+    text.push_line("");
+
+    for ((old, new), conflict) in renames.iter().zip(conflicts.iter()) {
+        let mut line = Line::from(vec![
+            Span::raw(old.to_owned()).red(),
+            Span::raw(" -> "),
+            Span::raw(new.to_owned()).green(),
+        ]);
+        if let Some(conflict) = conflict {
+            line.push_span(Span::raw(format!("  ({})", conflict)).red().bold());
+        }
+        text.push_line(line);
+    }
+
+    if conflict_count > 0 || renames.is_empty() {
+        SwitchCase::new(
+            app,
+            |_, _, _| Ok(true),
+            true,
+            CmdContent::Text(text),
+            SwitchCaseData::None
+        );
+        return
+    }
+
+    SwitchCase::new(
+        app,
+        rename_switch,
+        true,
+        CmdContent::Text(text),
+        SwitchCaseData::Struct(Box::new(RenamePlan { path, renames }))
+    );
+}
+
+fn rename_switch(app: &mut App, key: char, data: SwitchCaseData) -> AppResult<bool> {
+    if key != 'y' {
+        return Ok(true)
+    }
+
+    let plan = if let SwitchCaseData::Struct(data) = data {
+        match data.as_any().downcast_ref::<RenamePlan>() {
+            Some(plan) => plan.to_owned(),
+            None => panic!("Unknow panic occurred at rename_switch in bulk_rename.rs!"),
+        }
+    } else {
+        panic!("Unexpected situation at rename_switch in bulk_rename.rs.")
+    };
+
+    apply_renames(app, plan)?;
+
+    Ok(true)
+}
+
+/// Rename files of PLAN, the files whose names are taken by others are moved
+/// to temporary names first, so that names can be swapped.
+fn apply_renames(app: &mut App, plan: RenamePlan) -> AppResult<()> {
+    let RenamePlan { path, renames } = plan;
+    let targets = renames.iter()
+        .map(|(_, new)| new.as_str())
+        .collect::<HashSet<_>>();
+
+    let mut errors = AppError::new();
+    let mut sources: Vec<Option<PathBuf>> = Vec::new();
+    for (idx, (old, _)) in renames.iter().enumerate() {
+        let old_path = path.join(old);
+        if !targets.contains(old.as_str()) {
+            sources.push(Some(old_path));
+            continue;
+        }
+
+        let temp_path = path.join(format!(".hire_rename_{}_{}", process::id(), idx));
+        match fs::rename(&old_path, &temp_path) {
+            Ok(_) => {
+                app.journal.record(FsOperation::Renamed(old_path, temp_path.to_owned()));
+                sources.push(Some(temp_path));
+            },
+            Err(err) => {
+                errors.add_error(err);
+                sources.push(None);
+            }
+        }
+    }
+
+    for (source, (_, new)) in sources.into_iter().zip(renames.iter()) {
+        let Some(source) = source else {
+            continue
+        };
+
+        let new_path = path.join(new);
+        match fs::rename(&source, &new_path) {
+            Ok(_) => app.journal.record(FsOperation::Renamed(source, new_path)),
+            Err(err) => errors.add_error(err),
+        }
+    }
+
+    app.clear_path_marked_files();
+
+    // Select the first renamed file.
+    let first = renames.first().map(|(_, new)| new.to_owned());
+    if app.hide_files && renames.iter().any(|(_, new)| new.starts_with('.')) {
+        app.hide_or_show(first)?;
+    } else {
+        app.update_with_prev_selected(first)?;
+    }
+
+    if !errors.is_empty() {
+        return Err(errors)
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_bulk_rename() {
+        assert_eq!(expand_template("photo_{n:03}.{ext}", "a.JPG", 7).unwrap(), "photo_007.JPG");
+        assert_eq!(expand_template("{stem}_{n}${1}", ".bashrc", 2).unwrap(), ".bashrc_2${1}");
+        assert!(expand_template("{size}", "a", 1).is_err());
+
+        assert_eq!(change_case("my photo-IMG.JPG", "title").unwrap(), "My Photo-Img.JPG");
+
+        let path = TestDir::new("bulk_rename");
+        fs::write(path.join("a"), "").unwrap();
+        fs::write(path.join("b"), "").unwrap();
+        fs::write(path.join("c"), "").unwrap();
+
+        let renames = [("a", "b"), ("b", "a"), ("c", "a"), ("x", "c/d")]
+            .map(|(old, new)| (old.to_owned(), new.to_owned()));
+        assert_eq!(
+            check_conflicts(&path, &renames),
+            vec![None, None, Some("duplicated"), Some("invalid name")]
+        );
+        assert_eq!(
+            check_conflicts(&path, &[(String::from("a"), String::from("c"))]),
+            vec![Some("already exists")]
+        );
+
+        // An existing file is never reused.
+        let first = create_temp_file(String::from("a\n")).unwrap();
+        let second = create_temp_file(String::from("b\n")).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "a\n");
+        assert_eq!(fs::metadata(&first).unwrap().permissions().mode() & 0o777, 0o600);
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+}
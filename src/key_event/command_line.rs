@@ -8,6 +8,7 @@ use crate::{
     utils::{Block, CursorPos},
     error::AppResult,
     utils::str_split,
+    command::COMMAND_NAMES,
     option_get,
     app::App,
 };
@@ -317,9 +318,17 @@ fn update_completion(
             }
         }
     } else {
-        let commands = ["rename", "create_file", "create_dir", "create_symlink"];
+        let commands = [
+            "rename", "create_file", "create_dir", "create_symlink",
+            "trash_list", "trash_restore", "trash_empty",
+            "job_cancel", "compress", "tab", "filter", "grep", "bulk_rename", "chmod", "chown"
+        ];
 
-        for cmd in commands.into_iter() {
+        // The commands of keymap can be run in command line as well.
+        let keymap_commands = COMMAND_NAMES.iter()
+            .filter(|cmd| !commands.contains(cmd));
+
+        for cmd in commands.iter().chain(keymap_commands) {
             if cmd.starts_with(current) {
                 if max_width < cmd.len() {
                     max_width = cmd.len();
@@ -18,6 +18,8 @@ use crate::{
 
 pub use types::*;
 
+use super::archive::is_archive;
+
 pub fn directory_movement(
     direction: Direction,
     app: &mut App,
@@ -64,12 +66,9 @@ pub fn directory_movement(
             if in_root {
                 // It seems impossible that the root directory is empty.
                 let selected_file = app.get_file_saver().unwrap();
-                if !selected_file.is_dir {
-                    super::shell::open_file_in_shell(
-                        app,
-                        terminal,
-                        app.current_path().join(&selected_file.name)
-                    )?;
+                let file_path = app.current_path().join(&selected_file.name);
+                if !selected_file.is_dir && !is_archive(&file_path) {
+                    super::shell::open_file_in_shell(app, terminal, file_path)?;
 
                     return Ok(())
                 }
@@ -88,13 +87,10 @@ pub fn directory_movement(
 
                 let selected_file = selected_file.unwrap();
 
-                // Open selected file
-                if !selected_file.is_dir {
-                    super::shell::open_file_in_shell(
-                        app,
-                        terminal,
-                        app.current_path().join(&selected_file.name)
-                    )?;
+                // Open selected file, archives are entered as directories.
+                let file_path = app.current_path().join(&selected_file.name);
+                if !selected_file.is_dir && !is_archive(&file_path) {
+                    super::shell::open_file_in_shell(app, terminal, file_path)?;
                     return Ok(())
                 }
                 
@@ -114,6 +110,15 @@ pub fn directory_movement(
                     current_empty = true;
                 }
             }
+
+            // The entries of archive are not listed in child block before entering it.
+            if is_archive(&app.path) {
+                app.file_content.reset();
+                app.init_current_files()?;
+                app.selected_item.current_select(None);
+                app.refresh_current_item();
+                current_empty = app.current_files.is_empty();
+            }
             if !current_empty {
                 app.init_child_files()?;
             }
@@ -0,0 +1,159 @@
+// Directory Watcher
+
+use std::thread;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use inotify::{Inotify, WatchDescriptor, WatchMask, Watches};
+
+use super::archive::split_archive_path;
+
+use crate::app::App;
+use crate::error::AppResult;
+use crate::utils::SearchFile;
+
+/// Watch the directories shown in browser, so that changes made by other programs can be seen.
+#[derive(Default)]
+pub struct DirWatcher {
+    watches: Option<Watches>,
+    watched: HashMap<PathBuf, WatchDescriptor>,
+
+    /// The time of first change not refreshed yet.
+    /// Changes are coalesced for a while, so that a burst of events only refreshes once.
+    pending: Option<Instant>,
+}
+
+impl DirWatcher {
+    const DEBOUNCE: Duration = Duration::from_millis(150);
+
+    fn is_watched(&self, wd: &WatchDescriptor) -> bool {
+        self.watched.values().any(|_wd| _wd == wd)
+    }
+
+    /// Watch directories in PATHS and stop watching the others.
+    fn update(&mut self, paths: Vec<PathBuf>) {
+        let watches = if let Some(ref mut watches) = self.watches {
+            watches
+        } else {
+            return
+        };
+
+        self.watched.retain(|path, wd| {
+            if paths.contains(path) {
+                return true
+            }
+
+            // The watch is removed automatically when its directory is deleted.
+            let _ = watches.remove(wd.to_owned());
+            false
+        });
+
+        for path in paths.into_iter() {
+            if self.watched.contains_key(&path) {
+                continue;
+            }
+
+            let mask = WatchMask::CREATE |
+                WatchMask::DELETE |
+                WatchMask::MOVED_FROM |
+                WatchMask::MOVED_TO |
+                WatchMask::CLOSE_WRITE |
+                WatchMask::DELETE_SELF |
+                WatchMask::MOVE_SELF |
+                WatchMask::ONLYDIR;
+
+            if let Ok(wd) = watches.add(&path, mask) {
+                self.watched.insert(path, wd);
+            }
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    /// Return None when inotify is not available.
+    pub fn init_dir_watcher(&mut self) -> Option<Receiver<WatchDescriptor>> {
+        let mut inotify = Inotify::init().ok()?;
+        let (event_tx, event_rx) = mpsc::channel::<WatchDescriptor>();
+
+        self.dir_watcher.watches = Some(inotify.watches());
+
+        thread::spawn(move || {
+            let mut buffer = [0; 4096];
+
+            loop {
+                let events = match inotify.read_events_blocking(&mut buffer) {
+                    Ok(events) => events,
+                    Err(_) => break,
+                };
+
+                for event in events {
+                    if event_tx.send(event.wd).is_err() {
+                        return
+                    }
+                }
+            }
+        });
+
+        Some(event_rx)
+    }
+
+    /// Keep watching the parent, current & child directories.
+    pub fn update_watched_dirs(&mut self) {
+        if self.dir_watcher.watches.is_none() {
+            return
+        }
+
+        let mut paths: Vec<PathBuf> = Vec::new();
+        paths.push(
+            self.path.parent()
+                .unwrap_or(Path::new("/"))
+                .to_path_buf()
+        );
+
+        let current_path = self.current_path();
+        let child_dir = self.search_file(SearchFile::Current)
+            .filter(|file| file.is_dir)
+            .map(|file| current_path.join(&file.name));
+        if let Some(child_dir) = child_dir {
+            paths.push(child_dir);
+        }
+
+        if !paths.contains(&current_path) {
+            paths.push(current_path);
+        }
+
+        self.dir_watcher.update(paths);
+    }
+
+    /// Refresh file lists after the watched directories changed.
+    pub fn handle_dir_changes<I>(&mut self, events: I) -> AppResult<()>
+    where I: Iterator<Item = WatchDescriptor>
+    {
+        for wd in events {
+            if self.dir_watcher.pending.is_none() && self.dir_watcher.is_watched(&wd) {
+                self.dir_watcher.pending = Some(Instant::now());
+            }
+        }
+
+        let ready = self.dir_watcher.pending
+            .is_some_and(|since| since.elapsed() >= DirWatcher::DEBOUNCE);
+
+        // Avoid losing the modifications in edit mode.
+        if !ready || self.edit_mode.enabled {
+            return Ok(())
+        }
+        self.dir_watcher.pending = None;
+
+        // Current directory is removed, go to its nearest ancestor.
+        if !self.path.is_dir() && split_archive_path(&self.path).is_none() {
+            let mut path = self.path.to_owned();
+            while !path.is_dir() && path.pop() {}
+
+            return self.goto_dir(path, None)
+        }
+
+        self.update_with_prev_selected(None)
+    }
+}
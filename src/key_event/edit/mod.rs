@@ -10,7 +10,7 @@ use crate::{
     app::App, error::{AppError, AppResult}, option_get, utils::{delete_word, CmdContent, CursorPos, FileContent}
 };
 
-use super::{cursor_movement::move_cursor_core, Goto, SwitchCaseData};
+use super::{cursor_movement::move_cursor_core, trash, FsOperation, Goto, SwitchCaseData};
 
 pub use types::*;
 
@@ -314,14 +314,9 @@ pub fn save_edit(app: &mut App, key: char, _: SwitchCaseData) -> AppResult<bool>
             let file = app.current_files.get(idx).unwrap();
             let file_path = app.path.join(&file.name);
             if item.delete && !renamed_files.contains(&file.name) {
-                if file.is_dir {
-                    if let Err(err) = fs::remove_dir_all(file_path) {
-                        errors.add_error(err);
-                    }
-                } else {
-                    if let Err(err) = fs::remove_file(file_path) {
-                        errors.add_error(err);
-                    }
+                match trash::move_to_trash(file_path) {
+                    Ok(entry) => app.journal.record(FsOperation::Trashed(entry)),
+                    Err(err) => errors.add_error(err),
                 }
 
                 continue;
@@ -335,8 +330,9 @@ pub fn save_edit(app: &mut App, key: char, _: SwitchCaseData) -> AppResult<bool>
                 let new_path = app.path.join(&item.editing_name);
                 renamed_files.push(item.editing_name.to_owned());
 
-                if let Err(err) = fs::rename(file_path, new_path) {
-                    errors.add_error(err);
+                match fs::rename(&file_path, &new_path) {
+                    Ok(_) => app.journal.record(FsOperation::Renamed(file_path, new_path)),
+                    Err(err) => errors.add_error(err),
                 }
             }
 
@@ -348,14 +344,15 @@ pub fn save_edit(app: &mut App, key: char, _: SwitchCaseData) -> AppResult<bool>
         }
 
         let file_path = app.path.join(&item.editing_name);
-        if item.is_dir {
-            if let Err(err) = fs::create_dir(file_path) {
-                errors.add_error(err);
-            }
+        let create_result = if item.is_dir {
+            fs::create_dir(&file_path)
         } else {
-            if let Err(err) = fs::File::create_new(file_path) {
-                errors.add_error(err);
-            }
+            fs::File::create_new(&file_path).map(|_| ())
+        };
+
+        match create_result {
+            Ok(_) => app.journal.record(FsOperation::Created(file_path)),
+            Err(err) => errors.add_error(err),
         }
     }
 
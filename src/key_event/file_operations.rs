@@ -6,10 +6,13 @@ use std::collections::HashMap;
 use ratatui::{style::Stylize, text::{Line, Text}};
 
 use super::Goto;
+use super::trash;
+use super::FsOperation;
 use super::cursor_movement;
 use super::{SwitchCase, SwitchCaseData};
 
 use crate::app::App;
+use crate::command::quote_arg;
 use crate::utils::{CmdContent, CursorPos};
 use crate::error::{AppResult, AppError, ErrorType};
 
@@ -17,7 +20,8 @@ use crate::error::{AppResult, AppError, ErrorType};
 pub fn append_file_name(app: &mut App, to_end: bool) -> AppResult<()> {
     let file_saver = app.get_file_saver();
     if let Some(file_saver) = file_saver {
-        let current_file = &file_saver.name;
+        // The name is quoted to keep spaces, quotes & backslashes in it.
+        let current_file = quote_arg(&file_saver.name);
 
         if file_saver.is_dir || to_end {
             app.selected_block.set_command_line(
@@ -32,13 +36,14 @@ pub fn append_file_name(app: &mut App, to_end: bool) -> AppResult<()> {
             .rev()
             .position(|x| x == '.');
 
+        let name_len = current_file.chars().count();
         app.selected_block.set_command_line(
             format!(":rename {}", current_file),
             if let Some(idx) = cursor_pos {
-                let idx = current_file.len() - 1 - idx;
+                let idx = name_len - 1 - idx;
                 // In this condition,
                 // the file does not have string about its extension.
-                if idx == 0 {
+                if file_saver.name.rfind('.') == Some(0) {
                     CursorPos::End
                 } else {
                     CursorPos::Index(idx + 8)
@@ -55,12 +60,17 @@ pub fn append_file_name(app: &mut App, to_end: bool) -> AppResult<()> {
 }
 
 // Delete operation
-pub fn delete_operation(app: &mut App) {
+/// Delete files, which will be moved to trash unless PERMANENT is true.
+pub fn delete_operation(app: &mut App, permanent: bool) {
     SwitchCase::new(
         app,
-        delete_switch,
+        if permanent {
+            permanent_delete_switch
+        } else {
+            delete_switch
+        },
         true,
-        generate_msg(app, false),
+        generate_msg(app, false, permanent),
         SwitchCaseData::None
     );
 }
@@ -70,6 +80,25 @@ fn delete_switch(
     key: char,
     data: SwitchCaseData
 ) -> AppResult<bool>
+{
+    delete_switch_core(app, key, data, false)
+}
+
+fn permanent_delete_switch(
+    app: &mut App,
+    key: char,
+    data: SwitchCaseData
+) -> AppResult<bool>
+{
+    delete_switch_core(app, key, data, true)
+}
+
+fn delete_switch_core(
+    app: &mut App,
+    key: char,
+    data: SwitchCaseData,
+    permanent: bool
+) -> AppResult<bool>
 {
     let in_root = app.path.to_string_lossy() == "/";
 
@@ -78,9 +107,13 @@ fn delete_switch(
             if let SwitchCaseData::None = data {
                 SwitchCase::new(
                     app,
-                    delete_switch,
+                    if permanent {
+                        permanent_delete_switch
+                    } else {
+                        delete_switch
+                    },
                     true,
-                    generate_msg(app, true),
+                    generate_msg(app, true, permanent),
                     SwitchCaseData::Bool(true)
                 );
 
@@ -103,7 +136,8 @@ fn delete_switch(
                             path,
                             files.files.into_iter(),
                             false,
-                            in_root
+                            in_root,
+                            permanent
                         )?;
                     }
                     app.goto_dir(current_dir, None)?;
@@ -127,7 +161,8 @@ fn delete_switch(
                         app.current_path(),
                         temp_hashmap.into_iter(),
                         true,
-                        in_root
+                        in_root,
+                        permanent
                     )?;
                 } else {
                     return Err(ErrorType::NoSelected.pack())
@@ -140,8 +175,12 @@ fn delete_switch(
     Ok(true)
 }
 
-fn generate_msg(app: &App, confirm: bool) -> CmdContent {
-    let mut msg = String::from("[D] delete files\n\n");
+fn generate_msg(app: &App, confirm: bool, permanent: bool) -> CmdContent {
+    let mut msg = String::from(if permanent {
+        "[D] delete files permanently\n\n"
+    } else {
+        "[D] move files to trash\n\n"
+    });
 
     if app.marked_files.is_empty() {
         if let Some(file) = app.get_file_saver() {
@@ -191,9 +230,11 @@ fn generate_msg(app: &App, confirm: bool) -> CmdContent {
 
     if confirm {
         text.push_line("");
-        text.push_line(Line::raw(
-            "Are you sure to remove these files? (y to confirm)"
-        ).red());
+        text.push_line(Line::raw(if permanent {
+            "Are you sure to remove these files permanently? (y to confirm)"
+        } else {
+            "Are you sure to move these files to trash? (y to confirm)"
+        }).red());
     }
 
     CmdContent::Text(text)
@@ -235,11 +276,13 @@ pub fn mark_operation(app: &mut App,
     Err(ErrorType::NoSelected.pack())
 }
 
+/// Delete files in PATH, the files will be moved to trash when PERMANENT is false.
 pub fn delete_file<I>(app: &mut App,
                       path: PathBuf,
                       file_iter: I,
                       single_file: bool,
-                      in_root: bool
+                      in_root: bool,
+                      permanent: bool
 ) -> AppResult<()>
 where I: Iterator<Item = (String, bool)>
 {
@@ -251,7 +294,11 @@ where I: Iterator<Item = (String, bool)>
         let is_dir = file.1;
         let full_file = path.join(&file.0);
 
-        let remove_result = if is_dir {
+        let remove_result = if !permanent {
+            trash::move_to_trash(full_file).map(|entry| {
+                app.journal.record(FsOperation::Trashed(entry));
+            })
+        } else if is_dir {
             remove_dir_all(full_file)
         } else {
             remove_file(full_file)
@@ -0,0 +1,328 @@
+// File Search
+
+mod pattern;
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread
+};
+
+use anyhow::bail;
+
+use crate::{
+    rt_error,
+    error::AppResult,
+    key_event::Goto,
+    app::FileSaver,
+    utils::Block,
+};
+
+use super::App;
+
+pub use pattern::{SearchMode, SearchPattern};
+
+/// The files matching search pattern.
+#[derive(Default)]
+pub struct SearchResult {
+    /// Ordered by score when the pattern is ranked, otherwise by position.
+    indexes: Vec<usize>,
+
+    /// Positions of matched characters in file names.
+    highlights: HashMap<usize, Vec<usize>>,
+    ranked: bool,
+}
+
+/// The filter hiding unmatched files in a directory.
+pub struct FileFilter {
+    path: PathBuf,
+    input: String,
+    pattern: SearchPattern,
+}
+
+impl FileFilter {
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Whether the filter is used in directory PATH.
+    pub fn applies(&self, path: &Path) -> bool {
+        self.path == path
+    }
+
+    /// Whether the file NAME in directory PATH is shown.
+    pub fn is_match(&self, path: &Path, name: &str) -> bool {
+        !self.applies(path) || self.pattern.find(name).is_some()
+    }
+}
+
+#[derive(Default)]
+pub struct FileSearcher {
+    result: SearchResult,
+
+    /// The directory being searched.
+    path: PathBuf,
+
+    /// The mode used without prefix in search input.
+    pub mode: SearchMode,
+
+    calc_sender: Option<Sender<(SearchPattern, Vec<FileSaver>)>>
+}
+
+impl FileSearcher {
+    pub fn update(&mut self, result: SearchResult) {
+        self.result = result;
+    }
+
+    /// Positions of matched characters in file names of directory PATH.
+    pub fn highlights(&self, path: &Path) -> Option<&HashMap<usize, Vec<usize>>> {
+        let highlights = &self.result.highlights;
+        (self.path == path && !highlights.is_empty()).then_some(highlights)
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn init_search_channel(&mut self) -> Receiver<SearchResult> {
+        let (update_tx, update_rx) = mpsc::channel::<SearchResult>();
+        let (calc_tx, calc_rx)     = mpsc::channel::<(SearchPattern, Vec<FileSaver>)>();
+
+        thread::spawn(move || loop {
+            if let Ok((pattern, files)) = calc_rx.recv() {
+                update_tx.send(search_core(&pattern, &files))
+                    .expect("Error occurred from channel when searching file!")
+            }
+        });
+
+        self.file_searcher.calc_sender = Some(calc_tx);
+
+        update_rx
+    }
+
+    fn search_pattern(&mut self, name: &str, exactly: bool) -> anyhow::Result<SearchPattern> {
+        self.file_searcher.path = self.path.to_owned();
+
+        if exactly {
+            return Ok(SearchPattern::Exact(name.to_owned()))
+        }
+
+        self.command_history.push(format!("/{}", name));
+        SearchPattern::new(name, self.file_searcher.mode)
+    }
+
+    pub fn file_search_sync(
+        &mut self,
+        name: String,
+        exactly: bool
+    ) -> AppResult<()>
+    {
+        let pattern = self.search_pattern(&name, exactly)?;
+        let current_files = self.get_directory_mut().0;
+        let result = search_core(&pattern, current_files);
+
+        if !result.indexes.is_empty() {
+            self.file_searcher.update(result);
+            self.first_candidate()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn file_search(&mut self, name: String, exactly: bool) -> anyhow::Result<()> {
+        // NOTE: The user cannot reach to `exactly` by inputing command.
+        let pattern = self.search_pattern(&name, exactly)?;
+
+        let current_files = self.get_directory_mut().0.to_owned();
+        if let Some(ref sender) = self.file_searcher.calc_sender {
+            if let Err(err) = sender.send((pattern, current_files)) {
+                return Err(err.into())
+            }
+
+            return Ok(())
+        }
+
+        bail!("Cannot find sender in file_searcher")
+    }
+
+    /// Move to the first candidate after searching.
+    /// It's the best matched file when ranked, otherwise the next one from cursor.
+    pub fn first_candidate(&mut self) -> AppResult<()> {
+        use crate::key_event::move_cursor;
+
+        if !self.file_searcher.result.ranked {
+            return self.next_candidate()
+        }
+
+        if let Some(idx) = self.file_searcher.result.indexes.first() {
+            let in_root = matches!(self.selected_block, Block::Browser(true));
+            move_cursor(self, Goto::Index(*idx), in_root)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn prev_candidate(&mut self) -> AppResult<()> {
+        self.move_candidate(false)?;
+
+        Ok(())
+    }
+
+    pub fn next_candidate(&mut self) -> AppResult<()> {
+        self.move_candidate(true)?;
+
+        Ok(())
+    }
+
+    /// Move current cursor to next/previous searched file name.
+    /// When NEXT is true, searching the next. Otherwise the previous.
+    fn move_candidate(&mut self,
+                      next: bool
+    ) -> AppResult<()>
+    {
+        use crate::key_event::move_cursor;
+
+        let candidates = &self.file_searcher.result.indexes;
+
+        let in_root = if let Block::Browser(true) = self.selected_block {
+            true
+        } else {
+            false
+        };
+
+        let current_idx = if in_root {
+            self.selected_item.parent.selected().unwrap()
+        } else {
+            self.selected_item.current.selected().unwrap()
+        };
+
+        let target = if self.file_searcher.result.ranked {
+            get_ranked_index(candidates, current_idx, next)
+        } else if next {
+            get_search_index(candidates.iter(), current_idx, true)
+        } else {
+            get_search_index(candidates.iter().rev(), current_idx, false)
+        };
+
+        if let Some(idx) = target {
+            move_cursor(self, Goto::Index(idx), in_root)?;
+        }
+
+        Ok(())
+    }
+    
+    /// Set the default search mode, or switch to the next one when MODE is None.
+    pub fn search_mode(&mut self, mode: Option<SearchMode>) {
+        self.file_searcher.mode = mode.unwrap_or(self.file_searcher.mode.next());
+    }
+
+    /// Filter files of current directory with INPUT, the filter is cleared when it's None.
+    pub fn set_filter(&mut self, input: Option<&str>) -> AppResult<()> {
+        let filter = if let Some(input) = input {
+            let filter = FileFilter {
+                path: self.path.to_owned(),
+                input: input.to_owned(),
+                pattern: SearchPattern::new(input, self.file_searcher.mode)?,
+            };
+
+            // Files hidden by the previous filter are matched as well.
+            let previous = self.file_filter.take();
+            let files = self.read_files(&filter.path);
+            self.file_filter = previous;
+
+            if !files?.iter().any(|file| filter.pattern.find(&file.name).is_some()) {
+                rt_error!(format!("No file matches the filter {}", input))
+            }
+
+            Some(filter)
+        } else {
+            if self.file_filter.is_none() {
+                return Ok(())
+            }
+
+            None
+        };
+
+        self.file_filter = filter;
+        self.clean_search_idx();
+        self.update_with_prev_selected(None)
+    }
+
+    pub fn clean_search_idx(&mut self) {
+        self.file_searcher.result = SearchResult::default();
+    }
+}
+
+/// Core function of file searcher.
+fn search_core(pattern: &SearchPattern, files: &Vec<FileSaver>) -> SearchResult {
+    let mut result = SearchResult {
+        ranked: pattern.ranked(),
+        ..Default::default()
+    };
+
+    let mut scores = Vec::new();
+    for (idx, file) in files.iter().enumerate() {
+        if let Some((positions, score)) = pattern.find(&file.name) {
+            scores.push((idx, score));
+            if !positions.is_empty() {
+                result.highlights.insert(idx, positions);
+            }
+
+            if let SearchPattern::Exact(_) = pattern {
+                break;
+            }
+        }
+    }
+
+    if result.ranked {
+        // The sort is stable, files with the same score keep their order.
+        scores.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    }
+    result.indexes = scores.into_iter().map(|(idx, _)| idx).collect();
+
+    result
+}
+
+/// Get the candidate next to CURRENT in ranked order.
+/// The best one is returned when CURRENT is not a candidate.
+fn get_ranked_index(candidates: &[usize], current: usize, next: bool) -> Option<usize> {
+    let Some(pos) = candidates.iter().position(|idx| *idx == current) else {
+        return candidates.first().copied()
+    };
+
+    if next {
+        candidates.get(pos + 1).copied()
+    } else {
+        pos.checked_sub(1).map(|pos| candidates[pos])
+    }
+}
+
+#[inline]
+fn get_search_index<'a, T>(iter: T,
+                           current: usize,
+                           next: bool
+) -> Option<usize>
+where T: Iterator<Item = &'a usize>
+{
+    let mut get_current_idx = false;
+
+    for i in iter {
+        if get_current_idx {
+            return Some(*i)
+        }
+
+        if !next && *i < current {
+            return Some(*i)
+        }
+
+        if next && *i > current {
+            return Some(*i)
+        }
+
+        if *i == current {
+            get_current_idx = true;
+            continue;
+        }
+    }
+
+    None
+}
@@ -0,0 +1,242 @@
+// Search Pattern
+
+use anyhow::{bail, Result};
+use globset::{GlobBuilder, GlobMatcher};
+use regex::{Regex, RegexBuilder};
+
+/// The way to match file names when searching.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    #[default]
+    Substring,
+    Regex,
+    Glob,
+    Fuzzy,
+}
+
+const MODES: [SearchMode; 4] = [
+    SearchMode::Substring,
+    SearchMode::Regex,
+    SearchMode::Glob,
+    SearchMode::Fuzzy,
+];
+
+impl SearchMode {
+    pub fn from_str(name: &str) -> Result<Self> {
+        match MODES.into_iter().find(|mode| mode.name() == name) {
+            Some(mode) => Ok(mode),
+            None => bail!("Unknow search mode: {}", name)
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SearchMode::Substring => "substring",
+            SearchMode::Regex     => "regex",
+            SearchMode::Glob      => "glob",
+            SearchMode::Fuzzy     => "fuzzy",
+        }
+    }
+
+    /// The prefix of search input to use this mode.
+    fn prefix(self) -> &'static str {
+        match self {
+            SearchMode::Substring => "sub:",
+            SearchMode::Regex     => "re:",
+            SearchMode::Glob      => "glob:",
+            SearchMode::Fuzzy     => "fuzzy:",
+        }
+    }
+
+    /// The next mode when toggling.
+    pub fn next(self) -> Self {
+        let idx = MODES.iter().position(|mode| *mode == self).unwrap();
+        MODES[(idx + 1) % MODES.len()]
+    }
+
+    /// Split the mode prefix from INPUT, the DEFAULT mode is used without prefix.
+    pub fn split_prefix(input: &str, default: Self) -> (Self, &str) {
+        MODES.into_iter()
+            .find_map(|mode| input.strip_prefix(mode.prefix()).map(|rest| (mode, rest)))
+            .unwrap_or((default, input))
+    }
+}
+
+/// Compiled search input.
+/// Patterns are case insensitive unless there's an uppercase character (smart case).
+pub enum SearchPattern {
+    /// Match the whole file name with case sensitivity, which is used to locate a file.
+    Exact(String),
+    Substring(Vec<char>, bool),
+    Regex(Regex),
+    Glob(GlobMatcher),
+    Fuzzy(Vec<char>, bool),
+}
+
+impl SearchPattern {
+    pub fn new(input: &str, default: SearchMode) -> Result<Self> {
+        let (mode, query) = SearchMode::split_prefix(input, default);
+        let ignore_case = !query.chars().any(char::is_uppercase);
+
+        let pattern = match mode {
+            SearchMode::Substring => Self::Substring(lower_chars(query, ignore_case), ignore_case),
+            SearchMode::Fuzzy => Self::Fuzzy(lower_chars(query, ignore_case), ignore_case),
+
+            SearchMode::Regex => Self::Regex(
+                RegexBuilder::new(query)
+                    .case_insensitive(ignore_case)
+                    .build()?
+            ),
+
+            SearchMode::Glob => Self::Glob(
+                GlobBuilder::new(query)
+                    .case_insensitive(ignore_case)
+                    .literal_separator(true)
+                    .build()?
+                    .compile_matcher()
+            ),
+        };
+
+        Ok(pattern)
+    }
+
+    /// Whether the matched files are ordered by score.
+    pub fn ranked(&self) -> bool {
+        matches!(self, Self::Fuzzy(..))
+    }
+
+    /// Match NAME, return the positions of matched characters with the score.
+    pub fn find(&self, name: &str) -> Option<(Vec<usize>, i64)> {
+        match self {
+            Self::Exact(query) => (name == query).then(|| (Vec::new(), 0)),
+
+            Self::Substring(query, ignore_case) => {
+                let name = lower_chars(name, *ignore_case);
+                if query.is_empty() {
+                    return Some((Vec::new(), 0))
+                }
+
+                let start = name.windows(query.len()).position(|window| window == query)?;
+                Some(((start..start + query.len()).collect(), 0))
+            },
+
+            Self::Regex(regex) => {
+                if !regex.is_match(name) {
+                    return None
+                }
+
+                let ranges = regex.find_iter(name)
+                    .map(|found| found.range())
+                    .collect::<Vec<_>>();
+                let positions = name.char_indices()
+                    .enumerate()
+                    .filter(|(_, (byte, _))| ranges.iter().any(|range| range.contains(byte)))
+                    .map(|(idx, _)| idx)
+                    .collect();
+
+                Some((positions, 0))
+            },
+
+            Self::Glob(matcher) => matcher.is_match(name)
+                .then(|| ((0..name.chars().count()).collect(), 0)),
+
+            Self::Fuzzy(query, ignore_case) => fuzzy_match(query, name, *ignore_case),
+        }
+    }
+}
+
+/// Characters of NAME, which are lowercase when IGNORE_CASE is true.
+/// A character is always mapped to one character to keep the positions.
+fn lower_chars(name: &str, ignore_case: bool) -> Vec<char> {
+    name.chars()
+        .map(|c| if ignore_case {
+            c.to_lowercase().next().unwrap_or(c)
+        } else {
+            c
+        })
+        .collect()
+}
+
+/// Match characters of QUERY in order, return the positions with the highest score.
+fn fuzzy_match(query: &[char], name: &str, ignore_case: bool) -> Option<(Vec<usize>, i64)> {
+    let origin = name.chars().collect::<Vec<_>>();
+    let chars = lower_chars(name, ignore_case);
+
+    let Some(first) = query.first() else {
+        return Some((Vec::new(), 0))
+    };
+
+    // Try each start of the first character, then match the rest greedily.
+    (0..chars.len())
+        .filter(|start| chars[*start] == *first)
+        .filter_map(|start| {
+            let mut positions = vec![start];
+            for c in query[1..].iter() {
+                let last = positions[positions.len() - 1];
+                let idx = chars[last + 1..].iter().position(|x| x == c)?;
+                positions.push(last + 1 + idx);
+            }
+
+            let score = fuzzy_score(&origin, &positions);
+            Some((positions, score))
+        })
+        .max_by_key(|(positions, score)| (*score, std::cmp::Reverse(positions[0])))
+}
+
+/// Consecutive characters & characters at the beginning of words get more score,
+/// while gaps between characters reduce it.
+fn fuzzy_score(name: &[char], positions: &[usize]) -> i64 {
+    let mut score = -(positions[0].min(10) as i64);
+
+    for (idx, pos) in positions.iter().enumerate() {
+        score += 16;
+
+        if idx > 0 {
+            let gap = pos - positions[idx - 1] - 1;
+            if gap == 0 {
+                score += 16;
+            } else {
+                score -= gap.min(16) as i64;
+            }
+        }
+
+        let boundary = match pos.checked_sub(1).map(|prev| name[prev]) {
+            None => true,
+            Some(prev) => !prev.is_alphanumeric() ||
+                (prev.is_lowercase() && name[*pos].is_uppercase())
+        };
+        if boundary {
+            score += 24;
+        }
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_pattern() {
+        let pattern = SearchPattern::new("re:^a.c", SearchMode::Substring).unwrap();
+        assert_eq!(pattern.find("ABCD").unwrap().0, vec![0, 1, 2]);
+        assert!(pattern.find("xabc").is_none());
+
+        let pattern = SearchPattern::new("glob:*.rs", SearchMode::Substring).unwrap();
+        assert!(pattern.find("main.RS").is_some());
+        assert!(pattern.find("main.rs.bak").is_none());
+
+        // Smart case
+        let pattern = SearchPattern::new("Rea", SearchMode::Substring).unwrap();
+        assert!(pattern.find("README.md").is_none());
+        assert_eq!(pattern.find("my Readme").unwrap().0, vec![3, 4, 5]);
+
+        let pattern = SearchPattern::new("fm", SearchMode::Fuzzy).unwrap();
+        let (positions, word) = pattern.find("file_mode.rs").unwrap();
+        assert_eq!(positions, vec![0, 5]);
+        let (_, inner) = pattern.find("xfxxxm").unwrap();
+        assert!(word > inner);
+        assert!(pattern.find("mf").is_none());
+    }
+}
@@ -0,0 +1,370 @@
+// Recursive Fuzzy Finder
+
+use std::mem;
+use std::cmp::Reverse;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use ignore::WalkBuilder;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::file_search::{SearchMode, SearchPattern};
+
+use crate::app::App;
+use crate::error::AppResult;
+use crate::option_get;
+
+/// The max number of walked paths, avoiding exhausting memory in a huge tree.
+const MAX_PATHS: usize = 200_000;
+
+/// Walked paths are sent to the finder in batches.
+const BATCH_SIZE: usize = 512;
+
+/// Popup to find files under a directory recursively.
+pub struct Finder {
+    root: PathBuf,
+    query: String,
+    pattern: Option<SearchPattern>,
+
+    /// Paths relative to root, directories end with `/`.
+    paths: Vec<String>,
+
+    /// Matched paths with positions of matched characters, ordered by score.
+    matches: Vec<FinderMatch>,
+    selected: usize,
+
+    /// The first match displayed in popup.
+    offset: usize,
+
+    receiver: Receiver<Vec<String>>,
+    cancel: Arc<AtomicBool>,
+    walking: bool,
+}
+
+struct FinderMatch {
+    idx: usize,
+    score: i64,
+    positions: Vec<usize>,
+}
+
+impl Finder {
+    /// Walk ROOT in background, files ignored by git are skipped.
+    pub fn new(root: PathBuf, hide_files: bool) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let walk_root = root.to_owned();
+        let walk_cancel = Arc::clone(&cancel);
+        thread::spawn(move || {
+            let walker = WalkBuilder::new(&walk_root)
+                .hidden(hide_files)
+                .build()
+                .flatten()
+                .filter(|entry| entry.depth() > 0)
+                .take(MAX_PATHS);
+
+            let mut batch = Vec::new();
+            for entry in walker {
+                if walk_cancel.load(Ordering::Relaxed) {
+                    return
+                }
+
+                let Ok(path) = entry.path().strip_prefix(&walk_root) else {
+                    continue
+                };
+                let mut path = path.to_string_lossy().into_owned();
+                if entry.file_type().is_some_and(|file_type| file_type.is_dir()) {
+                    path.push('/');
+                }
+
+                batch.push(path);
+                if batch.len() >= BATCH_SIZE && sender.send(mem::take(&mut batch)).is_err() {
+                    return
+                }
+            }
+
+            let _ = sender.send(batch);
+        });
+
+        Finder {
+            root,
+            query: String::new(),
+            pattern: SearchPattern::new("", SearchMode::Fuzzy).ok(),
+            paths: Vec::new(),
+            matches: Vec::new(),
+            selected: 0,
+            offset: 0,
+            receiver,
+            cancel,
+            walking: true,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn walking(&self) -> bool {
+        self.walking
+    }
+
+    /// Format: (matched, total)
+    pub fn count(&self) -> (usize, usize) {
+        (self.matches.len(), self.paths.len())
+    }
+
+    /// Receive the paths walked since last update.
+    pub fn update(&mut self) {
+        let start = self.paths.len();
+        while self.walking {
+            match self.receiver.try_recv() {
+                Ok(paths) => self.paths.extend(paths),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => self.walking = false,
+            }
+        }
+
+        if self.paths.len() > start {
+            self.match_paths(start);
+        }
+    }
+
+    /// Match the paths from START, then merge them into the ranked matches.
+    fn match_paths(&mut self, start: usize) {
+        let Some(ref pattern) = self.pattern else {
+            return
+        };
+
+        let mut matches: Vec<FinderMatch> = Vec::new();
+        for (idx, path) in self.paths.iter().enumerate().skip(start) {
+            if let Some((positions, score)) = pattern.find(path) {
+                matches.push(FinderMatch { idx, score, positions });
+            }
+        }
+
+        if !pattern.ranked() {
+            self.matches.extend(matches);
+            return
+        }
+
+        // Only the new matches are sorted, the previous ones have been ranked.
+        let paths = &self.paths;
+        let rank = |item: &FinderMatch| (Reverse(item.score), paths[item.idx].len(), item.idx);
+        matches.sort_by_key(rank);
+
+        let mut merged: Vec<FinderMatch> = Vec::with_capacity(self.matches.len() + matches.len());
+        let mut previous = mem::take(&mut self.matches).into_iter().peekable();
+        let mut matches = matches.into_iter().peekable();
+        loop {
+            let take_previous = match (previous.peek(), matches.peek()) {
+                (Some(a), Some(b)) => rank(a) <= rank(b),
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+
+            merged.extend(if take_previous { previous.next() } else { matches.next() });
+        }
+
+        self.matches = merged;
+    }
+
+    fn set_query(&mut self, query: String) {
+        let narrowing = self.pattern.is_some() && narrows(&self.query, &query);
+
+        // Invalid patterns (e.g. incomplete regex) match nothing.
+        self.pattern = SearchPattern::new(&query, SearchMode::Fuzzy).ok();
+        self.query = query;
+        self.selected = 0;
+
+        let Some(ref pattern) = self.pattern else {
+            self.matches.clear();
+            return
+        };
+
+        if !narrowing {
+            self.matches.clear();
+            self.match_paths(0);
+            return
+        }
+
+        // Paths matching the new query are always in the previous matches.
+        let paths = &self.paths;
+        self.matches.retain_mut(|item| match pattern.find(&paths[item.idx]) {
+            Some((positions, score)) => {
+                item.positions = positions;
+                item.score = score;
+                true
+            },
+            None => false
+        });
+
+        if pattern.ranked() {
+            self.matches.sort_by_key(|item| {
+                (Reverse(item.score), paths[item.idx].len(), item.idx)
+            });
+        }
+    }
+
+    fn select(&mut self, next: bool) {
+        if next {
+            if self.selected + 1 < self.matches.len() {
+                self.selected += 1;
+            }
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    fn selected_path(&self) -> Option<PathBuf> {
+        let item = self.matches.get(self.selected)?;
+        Some(self.root.join(self.paths[item.idx].trim_end_matches('/')))
+    }
+
+    /// Get the matches displayed in HEIGHT lines, with the position of selected one.
+    pub fn window(&mut self, height: usize) -> (Vec<(&str, &[usize])>, usize) {
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        let items = self.matches.iter()
+            .skip(self.offset)
+            .take(height)
+            .map(|item| (self.paths[item.idx].as_str(), item.positions.as_slice()))
+            .collect();
+
+        (items, self.selected - self.offset)
+    }
+}
+
+impl Drop for Finder {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Whether the paths matching QUERY are a subset of those matching PREVIOUS,
+/// which is true when fuzzy or substring query is extended.
+fn narrows(previous: &str, query: &str) -> bool {
+    let (previous_mode, _) = SearchMode::split_prefix(previous, SearchMode::Fuzzy);
+    let (mode, _) = SearchMode::split_prefix(query, SearchMode::Fuzzy);
+
+    query.starts_with(previous) &&
+        mode == previous_mode &&
+        matches!(mode, SearchMode::Fuzzy | SearchMode::Substring)
+}
+
+/// Open finder in current directory.
+pub fn open_finder(app: &mut App) {
+    app.finder = Some(Finder::new(app.path.to_owned(), app.hide_files));
+}
+
+/// Handle KEY when finder is opened, return false if there's no finder.
+pub fn handle_key(key: KeyEvent, app: &mut App) -> AppResult<bool> {
+    let Some(ref mut finder) = app.finder else {
+        return Ok(false)
+    };
+
+    let ctrl = key.modifiers == KeyModifiers::CONTROL;
+    match key.code {
+        KeyCode::Esc => app.finder = None,
+        KeyCode::Char('g' | 'c') if ctrl => app.finder = None,
+
+        KeyCode::Enter => {
+            let target = finder.selected_path();
+            app.finder = None;
+
+            if let Some(path) = target {
+                jump_to_path(app, path)?;
+            }
+        },
+
+        KeyCode::Up => finder.select(false),
+        KeyCode::Down => finder.select(true),
+        KeyCode::Char('p' | 'k') if ctrl => finder.select(false),
+        KeyCode::Char('n' | 'j') if ctrl => finder.select(true),
+
+        KeyCode::Backspace => {
+            let mut query = finder.query.to_owned();
+            query.pop();
+            finder.set_query(query);
+        },
+
+        KeyCode::Char(c) if key.modifiers.is_empty() || key.modifiers == KeyModifiers::SHIFT => {
+            let query = format!("{}{}", finder.query, c);
+            finder.set_query(query);
+        },
+
+        _ => ()
+    }
+
+    Ok(true)
+}
+
+/// Go to PATH, the parent directory is entered with the file selected if it's a file.
+pub fn jump_to_path(app: &mut App, path: PathBuf) -> AppResult<()> {
+    if !path.is_file() {
+        app.goto_dir(path, None)?;
+        return Ok(())
+    }
+
+    let name = option_get!(path.file_name(), "Cannot find target file")
+        .to_string_lossy()
+        .into_owned();
+    let parent = option_get!(path.parent(), "Cannot find the directory of target file");
+
+    // Show hidden files when the target is hidden.
+    let hide_files = if name.starts_with('.') {
+        Some(false)
+    } else {
+        None
+    };
+    app.goto_dir(parent, hide_files)?;
+    app.file_search_sync(name, true)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_finder() {
+        let root = TestDir::new("finder");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+
+        let mut finder = Finder::new(root.to_owned(), true);
+        while finder.walking() {
+            thread::sleep(std::time::Duration::from_millis(10));
+            finder.update();
+        }
+        assert_eq!(finder.count(), (3, 3));
+
+        finder.set_query(String::from("smr"));
+        assert_eq!(finder.selected_path(), Some(root.join("src/main.rs")));
+
+        finder.set_query(String::from("re:\\.md$"));
+        assert_eq!(finder.selected_path(), Some(root.join("README.md")));
+
+        // Extending the query filters the previous matches.
+        finder.set_query(String::from("s"));
+        assert_eq!(finder.count(), (2, 3));
+        finder.set_query(String::from("sm"));
+        assert_eq!(finder.selected_path(), Some(root.join("src/main.rs")));
+        assert_eq!(finder.count(), (1, 3));
+
+        assert!(narrows("sm", "smr"));
+        assert!(!narrows("smr", "sm"));
+        assert!(!narrows("r", "re:"));
+        assert!(!narrows("re:a", "re:ab"));
+    }
+}
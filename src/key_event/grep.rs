@@ -0,0 +1,289 @@
+// Recursive Content Search
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use ignore::WalkBuilder;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::file_search::{SearchMode, SearchPattern};
+
+use crate::app::App;
+use crate::error::AppResult;
+use crate::rt_error;
+
+/// The max number of matched lines, the search stops after reaching it.
+const MAX_RESULTS: usize = 10_000;
+
+/// Files larger than it are skipped.
+const MAX_FILE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// The max number of characters shown for a matched line.
+const MAX_SNIPPET: usize = 256;
+
+/// Lines shown above the matched line in file preview.
+const PREVIEW_CONTEXT: usize = 3;
+
+/// Results of `:grep`, shown in the expanded command line.
+pub struct GrepSearch {
+    root: PathBuf,
+    query: String,
+    results: Vec<GrepMatch>,
+    selected: usize,
+
+    /// The first result displayed in list.
+    offset: usize,
+
+    /// Whether the result list is shown.
+    shown: bool,
+
+    receiver: Receiver<Vec<GrepMatch>>,
+    cancel: Arc<AtomicBool>,
+    searching: bool,
+}
+
+pub struct GrepMatch {
+    /// Path relative to root.
+    pub path: String,
+    pub line: usize,
+    pub snippet: String,
+
+    /// Positions of matched characters in snippet.
+    pub positions: Vec<usize>,
+}
+
+impl GrepSearch {
+    /// Search files under ROOT in background, files ignored by git are skipped.
+    pub fn new(root: PathBuf, query: &str, hide_files: bool) -> AppResult<Self> {
+        let pattern = SearchPattern::new(query, SearchMode::Substring)?;
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let walk_root = root.to_owned();
+        let walk_cancel = Arc::clone(&cancel);
+        thread::spawn(move || {
+            let walker = WalkBuilder::new(&walk_root)
+                .hidden(hide_files)
+                .build()
+                .flatten()
+                .filter(|entry| entry.file_type().is_some_and(|file_type| file_type.is_file()));
+
+            let mut count = 0;
+            for entry in walker {
+                if walk_cancel.load(Ordering::Relaxed) || count >= MAX_RESULTS {
+                    return
+                }
+
+                let Ok(path) = entry.path().strip_prefix(&walk_root) else {
+                    continue
+                };
+                let path = path.to_string_lossy();
+
+                let mut matches = search_file(entry.path(), &pattern, &path);
+                matches.truncate(MAX_RESULTS - count);
+                count += matches.len();
+
+                if !matches.is_empty() && sender.send(matches).is_err() {
+                    return
+                }
+            }
+        });
+
+        Ok(GrepSearch {
+            root,
+            query: query.to_owned(),
+            results: Vec::new(),
+            selected: 0,
+            offset: 0,
+            shown: true,
+            receiver,
+            cancel,
+            searching: true,
+        })
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn searching(&self) -> bool {
+        self.searching
+    }
+
+    pub fn shown(&self) -> bool {
+        self.shown
+    }
+
+    pub fn count(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Receive the results found since last update.
+    pub fn update(&mut self) {
+        while self.searching {
+            match self.receiver.try_recv() {
+                Ok(matches) => self.results.extend(matches),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => self.searching = false,
+            }
+        }
+    }
+
+    fn select(&mut self, next: bool) {
+        if next {
+            if self.selected + 1 < self.results.len() {
+                self.selected += 1;
+            }
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    /// Get the results displayed in HEIGHT lines, with the position of selected one.
+    pub fn window(&mut self, height: usize) -> (&[GrepMatch], usize) {
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        let end = (self.offset + height).min(self.results.len());
+        (&self.results[self.offset..end], self.selected - self.offset)
+    }
+}
+
+impl Drop for GrepSearch {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Match each line of file at PATH, NAME is the path shown in results.
+fn search_file(path: &Path, pattern: &SearchPattern, name: &str) -> Vec<GrepMatch> {
+    let too_large = fs::metadata(path).map_or(true, |metadata| metadata.len() > MAX_FILE_SIZE);
+    if too_large {
+        return Vec::new()
+    }
+
+    // Binary files are skipped.
+    let Ok(bytes) = fs::read(path) else {
+        return Vec::new()
+    };
+    if bytes.iter().take(8192).any(|byte| *byte == 0) {
+        return Vec::new()
+    }
+    let Ok(content) = String::from_utf8(bytes) else {
+        return Vec::new()
+    };
+
+    let mut matches = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let Some((positions, _)) = pattern.find(line) else {
+            continue
+        };
+
+        // Leading whitespaces are not shown.
+        let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+        let snippet = line.chars().skip(indent).take(MAX_SNIPPET).collect();
+        let positions = positions.into_iter()
+            .filter(|pos| *pos >= indent && *pos < indent + MAX_SNIPPET)
+            .map(|pos| pos - indent)
+            .collect();
+
+        matches.push(GrepMatch {
+            path: name.to_owned(),
+            line: idx + 1,
+            snippet,
+            positions,
+        });
+    }
+
+    matches
+}
+
+/// Search QUERY in files under current directory, or show the last results without QUERY.
+pub fn grep(app: &mut App, query: Option<&str>) -> AppResult<()> {
+    if let Some(query) = query {
+        app.grep = Some(GrepSearch::new(app.path.to_owned(), query, app.hide_files)?);
+        return Ok(())
+    }
+
+    match app.grep {
+        Some(ref mut grep) => grep.shown = true,
+        None => rt_error!("There's no result of grep")
+    }
+
+    Ok(())
+}
+
+/// Handle KEY when the results of grep are shown, return false if they're hidden.
+pub fn handle_key(key: KeyEvent, app: &mut App) -> AppResult<bool> {
+    let Some(ref mut grep) = app.grep else {
+        return Ok(false)
+    };
+    if !grep.shown {
+        return Ok(false)
+    }
+
+    let ctrl = key.modifiers == KeyModifiers::CONTROL;
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => grep.shown = false,
+        KeyCode::Char('g') if ctrl => grep.shown = false,
+
+        // Stop searching while keeping the results found.
+        KeyCode::Char('c') if ctrl => grep.cancel.store(true, Ordering::Relaxed),
+
+        KeyCode::Enter => {
+            grep.shown = false;
+            if let Some(result) = grep.results.get(grep.selected) {
+                let path = grep.root.join(&result.path);
+                let start = result.line.saturating_sub(PREVIEW_CONTEXT + 1);
+
+                app.preview_line = Some((path.to_owned(), start));
+                super::finder::jump_to_path(app, path)?;
+            }
+        },
+
+        KeyCode::Up | KeyCode::Char('k') => grep.select(false),
+        KeyCode::Down | KeyCode::Char('j') => grep.select(true),
+        KeyCode::Char('p') if ctrl => grep.select(false),
+        KeyCode::Char('n') if ctrl => grep.select(true),
+
+        KeyCode::Home | KeyCode::Char('g') => grep.selected = 0,
+        KeyCode::End | KeyCode::Char('G') => grep.selected = grep.results.len().saturating_sub(1),
+
+        _ => ()
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_grep() {
+        let root = TestDir::new("grep");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {\n    let answer = 42;\n}\n").unwrap();
+        std::fs::write(root.join("data.bin"), b"answer\0").unwrap();
+
+        let mut grep = GrepSearch::new(root.to_owned(), "answer", true).unwrap();
+        while grep.searching() {
+            thread::sleep(std::time::Duration::from_millis(10));
+            grep.update();
+        }
+
+        assert_eq!(grep.count(), 1);
+        let result = &grep.results[0];
+        assert_eq!((result.path.as_str(), result.line), ("src/main.rs", 2));
+        assert_eq!(result.snippet, "let answer = 42;");
+        assert_eq!(result.positions, vec![4, 5, 6, 7, 8, 9]);
+    }
+}
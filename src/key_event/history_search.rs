@@ -0,0 +1,124 @@
+// Reverse Search of Command History
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::App;
+use crate::command::{AppCommand, HistoryKind};
+use crate::config::KeymapMode;
+use crate::error::AppResult;
+use crate::utils::{Block, CursorPos};
+
+/// Incremental reverse search in the history ring of current command line.
+pub struct HistorySearch {
+    query: String,
+    kind: HistoryKind,
+
+    /// Index of current match in the history ring.
+    idx: Option<usize>,
+    failed: bool,
+
+    /// The content of command line before searching, restored when aborting.
+    origin: String,
+}
+
+impl HistorySearch {
+    /// The prompt displayed before current match.
+    pub fn prompt(&self) -> String {
+        format!(
+            "({}reverse-i-search)`{}': ",
+            if self.failed { "failed " } else { "" },
+            self.query
+        )
+    }
+}
+
+/// Start reverse search, or search older match when it's searching.
+pub fn history_search(app: &mut App) -> AppResult<()> {
+    let Block::CommandLine(ref content, cursor) = app.selected_block else {
+        return Ok(())
+    };
+
+    if cursor == CursorPos::None {
+        return Ok(())
+    }
+
+    match app.history_search.as_ref().map(|search| search.idx) {
+        Some(idx) => search_match(app, idx),
+        None => {
+            let origin = content.get().to_owned();
+            app.history_search = Some(HistorySearch {
+                query: String::new(),
+                kind: HistoryKind::of(&origin),
+                idx: None,
+                failed: false,
+                origin,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Search the latest entry containing query before END, which is exclusive.
+fn search_match(app: &mut App, end: Option<usize>) {
+    let Some(ref mut search) = app.history_search else {
+        return
+    };
+
+    let ring = app.command_history.ring(search.kind);
+    let end = end.unwrap_or(ring.len());
+    let found = ring[..end].iter().rposition(|entry| entry.contains(&search.query));
+
+    search.failed = found.is_none();
+    if let Some(idx) = found {
+        search.idx = Some(idx);
+        app.selected_block.set_command_line(ring[idx].to_owned(), CursorPos::End);
+    }
+}
+
+/// Handle KEY when searching, return false if the key should be handled as usual.
+pub fn handle_key(key: KeyEvent, app: &mut App) -> bool {
+    let Some(ref mut search) = app.history_search else {
+        return false
+    };
+
+    // Keep searching with the key bound to history_search.
+    let command = app.keymap.get(KeymapMode::Input, &[key.into()])
+        .and_then(|node| node.command());
+    if let Some(AppCommand::HistorySearch) = command {
+        return false
+    }
+
+    match key.code {
+        KeyCode::Char(c) if key.modifiers.is_empty() || key.modifiers == KeyModifiers::SHIFT => {
+            search.query.push(c);
+
+            // The current match is still available when it contains the new query.
+            let end = search.idx.map(|idx| idx + 1);
+            search_match(app, end);
+        },
+
+        KeyCode::Backspace => {
+            search.query.pop();
+            search_match(app, None);
+        },
+
+        // Abort searching & restore the origin content.
+        KeyCode::Char('g') if key.modifiers == KeyModifiers::CONTROL => {
+            let origin = std::mem::take(&mut search.origin);
+            app.history_search = None;
+            app.selected_block.set_command_line(origin, CursorPos::End);
+        },
+
+        // Accept current match.
+        KeyCode::Esc => app.history_search = None,
+
+        // Accept current match, then handle the key as usual, e.g. confirm it with Enter.
+        _ => {
+            app.history_search = None;
+            return false
+        }
+    }
+
+    true
+}
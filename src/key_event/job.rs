@@ -0,0 +1,336 @@
+// Background Jobs
+
+use std::{
+    thread,
+    path::PathBuf,
+    time::{Duration, Instant},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use ratatui::text::{Line, Text};
+
+use crate::app::App;
+use crate::{option_get, rt_error};
+use crate::utils::CmdContent;
+use crate::error::{AppError, AppResult, ErrorType, NotFoundType};
+
+use super::{FsOperation, SwitchCase, SwitchCaseData};
+
+/// The minimal interval between two progress messages of a job.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    Copy,
+    Move,
+    Compress,
+    Chmod,
+    Chown,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct JobProgress {
+    pub files: usize,
+    pub total_files: usize,
+    pub bytes: u64,
+    pub total_bytes: u64,
+}
+
+pub struct Job {
+    pub id: usize,
+    pub kind: JobKind,
+    pub target: PathBuf,
+    pub progress: JobProgress,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// The result returned from a finished job.
+pub struct JobResult {
+    pub errors: AppError,
+    pub operations: Vec<FsOperation>,
+}
+
+pub enum JobMessage {
+    Progress(usize, JobProgress),
+    Finished(usize, JobResult),
+}
+
+/// The handle passed into job thread to report progress & check cancellation.
+pub struct JobContext {
+    id: usize,
+    last_report: Instant,
+    progress: JobProgress,
+    cancelled: Arc<AtomicBool>,
+    sender: Sender<JobMessage>,
+}
+
+#[derive(Default)]
+pub struct JobManager {
+    next_id: usize,
+    jobs: Vec<Job>,
+    sender: Option<Sender<JobMessage>>,
+}
+
+impl JobKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobKind::Copy => "Copying",
+            JobKind::Move => "Moving",
+            JobKind::Compress => "Compressing",
+            JobKind::Chmod => "Changing mode",
+            JobKind::Chown => "Changing owner",
+        }
+    }
+}
+
+impl JobProgress {
+    pub fn percent(&self) -> u64 {
+        if self.total_bytes == 0 {
+            if self.total_files == 0 {
+                return 0
+            }
+
+            return (self.files * 100 / self.total_files) as u64
+        }
+
+        self.bytes * 100 / self.total_bytes
+    }
+}
+
+impl Job {
+    /// The short description of job progress.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} {}/{} {}%",
+            self.kind.name(),
+            self.progress.files,
+            self.progress.total_files,
+            self.progress.percent()
+        )
+    }
+}
+
+impl JobContext {
+    pub(super) fn new(id: usize, sender: Sender<JobMessage>, cancelled: Arc<AtomicBool>) -> Self {
+        JobContext {
+            id,
+            sender,
+            last_report: Instant::now(),
+            progress: JobProgress::default(),
+            cancelled,
+        }
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    pub fn set_total(&mut self, files: usize, bytes: u64) {
+        self.progress.total_files = files;
+        self.progress.total_bytes = bytes;
+        self.report(true);
+    }
+
+    pub fn add_progress(&mut self, files: usize, bytes: u64) {
+        self.progress.files += files;
+        self.progress.bytes += bytes;
+        self.report(false);
+    }
+
+    fn report(&mut self, force: bool) {
+        if force || self.last_report.elapsed() >= PROGRESS_INTERVAL {
+            self.last_report = Instant::now();
+            let _ = self.sender.send(JobMessage::Progress(self.id, self.progress));
+        }
+    }
+}
+
+impl JobManager {
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Job> {
+        self.jobs.iter()
+    }
+
+    /// Cancel the job with ID, return false if it doesn't exist.
+    pub fn cancel(&self, id: usize) -> bool {
+        if let Some(job) = self.jobs.iter().find(|job| job.id == id) {
+            job.cancelled.store(true, Ordering::Release);
+            return true
+        }
+
+        false
+    }
+
+    pub fn cancel_all(&self) {
+        for job in self.jobs.iter() {
+            job.cancelled.store(true, Ordering::Release);
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn init_job_channel(&mut self) -> Receiver<JobMessage> {
+        let (job_tx, job_rx) = mpsc::channel::<JobMessage>();
+        self.jobs.sender = Some(job_tx);
+
+        job_rx
+    }
+
+    /// Run FUNC on a new thread as a background job.
+    pub fn spawn_job<F>(
+        &mut self,
+        kind: JobKind,
+        target: PathBuf,
+        func: F
+    ) -> AppResult<()>
+    where F: FnOnce(&mut JobContext) -> JobResult + Send + 'static
+    {
+        let sender = if let Some(ref sender) = self.jobs.sender {
+            sender.to_owned()
+        } else {
+            rt_error!("Cannot find sender of background jobs")
+        };
+
+        self.jobs.next_id += 1;
+        let id = self.jobs.next_id;
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let mut context = JobContext::new(id, sender, Arc::clone(&cancelled));
+
+        thread::spawn(move || {
+            let result = func(&mut context);
+            let _ = context.sender.send(JobMessage::Finished(id, result));
+        });
+
+        self.jobs.jobs.push(Job {
+            id,
+            kind,
+            target,
+            cancelled,
+            progress: JobProgress::default(),
+        });
+
+        Ok(())
+    }
+
+    pub fn handle_job_message(&mut self, message: JobMessage) -> AppResult<()> {
+        match message {
+            JobMessage::Progress(id, progress) => {
+                if let Some(job) = self.jobs.jobs.iter_mut().find(|job| job.id == id) {
+                    job.progress = progress;
+                }
+            },
+
+            JobMessage::Finished(id, result) => {
+                self.jobs.jobs.retain(|job| job.id != id);
+
+                for operation in result.operations.into_iter() {
+                    self.journal.record(operation);
+                }
+                self.journal.commit();
+
+                self.update_with_prev_selected(None)?;
+
+                if !result.errors.is_empty() {
+                    return Err(result.errors)
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// Show running jobs, press their position in the list to cancel them.
+pub fn job_list(app: &mut App) -> AppResult<()> {
+    if app.jobs.is_empty() {
+        return Err(ErrorType::NotFound(
+            NotFoundType::Item(String::from("running job"))
+        ).pack())
+    }
+
+    let mut text = Text::raw(
+        "[0-9] cancel job at the position  [C] cancel all jobs  (:job_cancel <id> for any job)\n"
+    );
+    text.push_line("");
+
+    // Ids keep increasing, so the keys are positions of jobs in the list.
+    for (idx, job) in app.jobs.iter().enumerate() {
+        let key = if idx < 10 { idx.to_string() } else { String::from(" ") };
+        text.push_line(Line::raw(format!(
+            "[{}] {} -> {}  ({} / {})  id: {}",
+            key,
+            job.summary(),
+            job.target.to_string_lossy(),
+            file_size::fit_4(job.progress.bytes),
+            file_size::fit_4(job.progress.total_bytes),
+            job.id
+        )));
+    }
+
+    SwitchCase::new(
+        app,
+        job_switch,
+        true,
+        CmdContent::Text(text),
+        SwitchCaseData::None
+    );
+
+    Ok(())
+}
+
+/// Cancel jobs with their id, cancel all jobs when IDS is empty.
+pub fn cancel_jobs<'a, I>(app: &mut App, ids: I) -> AppResult<()>
+where I: Iterator<Item = &'a str>
+{
+    let mut errors = AppError::new();
+    let mut cancelled = false;
+
+    for id in ids {
+        cancelled = true;
+
+        let exists = id.parse::<usize>()
+            .map(|id| app.jobs.cancel(id))
+            .unwrap_or(false);
+
+        if !exists {
+            errors.add_error(ErrorType::NotFound(
+                NotFoundType::Item(format!("job {}", id))
+            ));
+        }
+    }
+
+    if !cancelled {
+        app.jobs.cancel_all();
+    }
+
+    if !errors.is_empty() {
+        return Err(errors)
+    }
+
+    Ok(())
+}
+
+fn job_switch(app: &mut App, key: char, _: SwitchCaseData) -> AppResult<bool> {
+    match key {
+        'C' => app.jobs.cancel_all(),
+        '0'..='9' => {
+            let idx = key as usize - '0' as usize;
+            let job_id = option_get!(
+                app.jobs.iter().nth(idx).map(|job| job.id),
+                format!("No job at position {}", idx)
+            );
+            app.jobs.cancel(job_id);
+        },
+        _ => ()
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,185 @@
+// Operation Journal
+
+use std::io;
+use std::mem::take;
+use std::path::{Path, PathBuf};
+
+use crate::app::App;
+use crate::rt_error;
+use crate::error::{AppError, AppResult};
+
+use super::trash::{self, TrashEntry};
+
+/// The maximum number of operation groups that can be undone.
+const JOURNAL_LIMIT: usize = 100;
+
+/// A file system mutation that has been done.
+#[derive(Clone)]
+pub enum FsOperation {
+    /// The file is renamed or moved from the first path to the second one.
+    Renamed(PathBuf, PathBuf),
+
+    /// The file is copied from the first path to the second one.
+    Copied(PathBuf, PathBuf),
+
+    /// The file is created, including symbolic links & restored files.
+    Created(PathBuf),
+
+    /// The file is moved to trash.
+    Trashed(TrashEntry),
+}
+
+/// Record file system mutations, operations made by a single key press are in the same group.
+#[derive(Default)]
+pub struct Journal {
+    pending: Vec<FsOperation>,
+    undo_list: Vec<Vec<FsOperation>>,
+    redo_list: Vec<Vec<FsOperation>>,
+}
+
+impl FsOperation {
+    /// Revert current operation and return the one which can redo it.
+    pub fn revert(self) -> io::Result<Self> {
+        match self {
+            FsOperation::Renamed(from, to) => {
+                if to.symlink_metadata().is_err() {
+                    return Err(not_found(&to))
+                }
+                if from.symlink_metadata().is_ok() {
+                    return Err(already_exists(&from))
+                }
+
+                trash::move_item(&to, &from)?;
+                Ok(FsOperation::Renamed(to, from))
+            },
+
+            FsOperation::Copied(_, to) | FsOperation::Created(to) => {
+                Ok(FsOperation::Trashed(trash::move_to_trash(to)?))
+            },
+
+            FsOperation::Trashed(entry) => {
+                trash::restore(&entry)?;
+                Ok(FsOperation::Created(entry.original_path))
+            },
+        }
+    }
+
+    /// The path of file that is affected by this operation.
+    pub fn target(&self) -> &Path {
+        match self {
+            FsOperation::Renamed(_, to) |
+            FsOperation::Copied(_, to) |
+            FsOperation::Created(to) => to,
+            FsOperation::Trashed(entry) => &entry.original_path,
+        }
+    }
+}
+
+impl Journal {
+    pub fn record(&mut self, operation: FsOperation) {
+        self.pending.push(operation);
+    }
+
+    /// Pack the operations recorded since last commit as a group to undo.
+    pub fn commit(&mut self) {
+        if self.pending.is_empty() {
+            return
+        }
+
+        self.undo_list.push(take(&mut self.pending));
+        self.redo_list.clear();
+
+        if self.undo_list.len() > JOURNAL_LIMIT {
+            self.undo_list.remove(0);
+        }
+    }
+}
+
+pub fn undo(app: &mut App) -> AppResult<()> {
+    app.journal.commit();
+
+    if let Some(group) = app.journal.undo_list.pop() {
+        let (reverted, errors) = revert_group(group);
+        let target = selected_target(app, &reverted);
+        app.journal.redo_list.push(reverted);
+
+        return refresh(app, target, errors)
+    }
+
+    rt_error!("There's nothing to undo")
+}
+
+pub fn redo(app: &mut App) -> AppResult<()> {
+    app.journal.commit();
+
+    if let Some(group) = app.journal.redo_list.pop() {
+        let (reverted, errors) = revert_group(group);
+        let target = selected_target(app, &reverted);
+        app.journal.undo_list.push(reverted);
+
+        return refresh(app, target, errors)
+    }
+
+    rt_error!("There's nothing to redo")
+}
+
+/// Revert operations of GROUP from the last one, return operations to restore them.
+fn revert_group(group: Vec<FsOperation>) -> (Vec<FsOperation>, AppError) {
+    let mut errors = AppError::new();
+    let mut reverted: Vec<FsOperation> = Vec::new();
+
+    // The reverting operations are recorded in the order they're executed.
+    for operation in group.into_iter().rev() {
+        match operation.revert() {
+            Ok(_operation) => reverted.push(_operation),
+            Err(err) => errors.add_error(err),
+        }
+    }
+
+    (reverted, errors)
+}
+
+/// Get the name of file that should be selected after reverting.
+fn selected_target(app: &App, operations: &[FsOperation]) -> Option<String> {
+    let current_path = app.current_path();
+
+    for operation in operations.iter().rev() {
+        if let FsOperation::Trashed(_) = operation {
+            continue;
+        }
+
+        let target = operation.target();
+        if target.parent() == Some(current_path.as_path()) {
+            return target.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        }
+    }
+
+    None
+}
+
+fn refresh(app: &mut App, target: Option<String>, errors: AppError) -> AppResult<()> {
+    app.update_with_prev_selected(target)?;
+
+    if !errors.is_empty() {
+        return Err(errors)
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} doesn't exist", path.to_string_lossy())
+    )
+}
+
+#[inline]
+fn already_exists(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("{} already exists", path.to_string_lossy())
+    )
+}
@@ -0,0 +1,266 @@
+// Key Dispatch
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::option_get;
+use crate::app::App;
+use crate::utils::{Block, Direction};
+use crate::error::AppResult;
+use crate::command::AppCommand;
+use crate::config::{Key, KeymapMode};
+
+use super::{move_cursor, Goto};
+
+/// The time to wait for the next key when the pending keys are a complete sequence.
+const PENDING_TIMEOUT: Duration = Duration::from_millis(1000);
+
+const MAX_COUNT: usize = 9999;
+
+/// The keys pressed which are the prefix of key sequences.
+#[derive(Default)]
+pub struct PendingKeys {
+    keys: Vec<Key>,
+    since: Option<Instant>,
+
+    /// The count prefix of command, like `15j`.
+    count: Option<usize>,
+}
+
+impl PendingKeys {
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty() && self.count.is_none()
+    }
+
+    fn push(&mut self, key: Key) {
+        self.keys.push(key);
+        self.since = Some(Instant::now());
+    }
+
+    fn push_digit(&mut self, digit: usize) {
+        let count = self.count.unwrap_or(0) * 10 + digit;
+        self.count = Some(count.min(MAX_COUNT));
+    }
+
+    /// Clear the pending keys, return the count of them.
+    fn clear(&mut self) -> Option<usize> {
+        self.keys.clear();
+        self.since = None;
+        self.count.take()
+    }
+}
+
+impl fmt::Display for PendingKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(count) = self.count {
+            write!(f, "{}", count)?;
+        }
+
+        for key in self.keys.iter() {
+            write!(f, "{}", key)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Result of looking up the pending keys.
+enum KeyMatch {
+    Command(AppCommand),
+
+    /// More keys are required, the command is run when no key pressed in time.
+    Pending,
+
+    NotFound,
+}
+
+/// Dispatch KEY with the keymap of current mode.
+/// Return false if the key is not bound, which should be handled by built-in behaviours.
+pub fn dispatch_key(
+    key: KeyEvent,
+    app: &mut App,
+    terminal: &mut DefaultTerminal
+) -> AppResult<bool>
+{
+    dispatch(Key::from(key), app, terminal)
+}
+
+fn dispatch(key: Key, app: &mut App, terminal: &mut DefaultTerminal) -> AppResult<bool> {
+    let mode = keymap_mode(app);
+
+    if key == Key::new(KeyCode::Esc, KeyModifiers::NONE) && !app.pending_keys.is_empty() {
+        app.pending_keys.clear();
+        return Ok(true)
+    }
+
+    if let Some(digit) = count_digit(app, mode, key) {
+        app.pending_keys.push_digit(digit);
+        return Ok(true)
+    }
+
+    // Plain characters are inserted as text when inputting.
+    if matches!(mode, KeymapMode::Input) && app.pending_keys.is_empty() && key.is_plain_char() {
+        return Ok(false)
+    }
+
+    app.pending_keys.push(key);
+
+    match lookup(app, mode) {
+        KeyMatch::Command(command) => {
+            let count = app.pending_keys.clear();
+            run_command(command, count, app, terminal)?;
+        },
+
+        KeyMatch::Pending => (),
+
+        KeyMatch::NotFound => {
+            if app.pending_keys.keys.len() == 1 {
+                app.pending_keys.clear();
+                return Ok(false)
+            }
+
+            // Run the command of the prefix, then dispatch the new key again.
+            app.pending_keys.keys.pop();
+            let command = app.keymap.get(mode, &app.pending_keys.keys)
+                .and_then(|node| node.command())
+                .cloned();
+
+            let count = app.pending_keys.clear();
+            if let Some(command) = command {
+                run_command(command, count, app, terminal)?;
+            }
+
+            return dispatch(key, app, terminal)
+        }
+    }
+
+    Ok(true)
+}
+
+/// Run the command of pending keys if no key is pressed in time.
+pub fn pending_keys_timeout(app: &mut App, terminal: &mut DefaultTerminal) -> AppResult<()> {
+    let timeout = app.pending_keys.since
+        .is_some_and(|since| since.elapsed() >= PENDING_TIMEOUT);
+    if !timeout {
+        return Ok(())
+    }
+
+    let command = app.keymap.get(keymap_mode(app), &app.pending_keys.keys)
+        .and_then(|node| node.command())
+        .cloned();
+
+    let count = app.pending_keys.clear();
+    if let Some(command) = command {
+        run_command(command, count, app, terminal)?;
+        app.journal.commit();
+    }
+
+    Ok(())
+}
+
+/// Run COMMAND, which is repeated or applied with COUNT.
+fn run_command(
+    command: AppCommand,
+    count: Option<usize>,
+    app: &mut App,
+    terminal: &mut DefaultTerminal
+) -> AppResult<()>
+{
+    let in_root = in_root(app);
+    let count = match count {
+        Some(count) => count,
+        None => return command.execute(app, terminal, in_root)
+    };
+
+    match command {
+        AppCommand::ItemMove(direction @ (Direction::Up | Direction::Down)) => {
+            let selected = if in_root {
+                app.selected_item.parent_selected()
+            } else {
+                app.selected_item.current_selected()
+            };
+            let idx = option_get!(selected, "Cannot get current selected index");
+
+            move_cursor(app, Goto::Index(match direction {
+                Direction::Up => idx.saturating_sub(count),
+                _ => idx.saturating_add(count)
+            }), in_root)?;
+        },
+
+        AppCommand::SwitchTab(_) => {
+            let idx = option_get!(
+                char::from_digit(count as u32, 10),
+                format!("Cannot switch to tab {}", count)
+            );
+            AppCommand::SwitchTab(idx).execute(app, terminal, in_root)?;
+        },
+
+        AppCommand::ItemMove(_) | AppCommand::ListScroll(_) |
+        AppCommand::Mark(true) | AppCommand::MoveCandidate(_) |
+        AppCommand::MacroPlay(Some(_)) =>
+        {
+            for _ in 0..count {
+                command.to_owned().execute(app, terminal, in_root)?;
+            }
+        },
+
+        _ => command.execute(app, terminal, in_root)?
+    }
+
+    Ok(())
+}
+
+/// Get the digit of KEY if it's a part of count prefix.
+/// A count cannot start with `0` or a digit bound in keymap,
+/// digits are unbound in default keymaps so that counts like `15j` work.
+fn count_digit(app: &App, mode: KeymapMode, key: Key) -> Option<usize> {
+    if !matches!(mode, KeymapMode::Normal) || !app.pending_keys.keys.is_empty() {
+        return None
+    }
+
+    let digit = key.plain_char()?.to_digit(10)? as usize;
+    if app.pending_keys.count.is_none() &&
+        (digit == 0 || app.keymap.get(mode, std::slice::from_ref(&key)).is_some())
+    {
+        return None
+    }
+
+    Some(digit)
+}
+
+fn lookup(app: &App, mode: KeymapMode) -> KeyMatch {
+    match app.keymap.get(mode, &app.pending_keys.keys) {
+        Some(node) if node.is_prefix() => KeyMatch::Pending,
+        Some(node) => match node.command() {
+            Some(command) => KeyMatch::Command(command.to_owned()),
+            None => KeyMatch::NotFound
+        },
+        None => KeyMatch::NotFound
+    }
+}
+
+fn keymap_mode(app: &App) -> KeymapMode {
+    if let Block::CommandLine(_, _) = app.selected_block {
+        return KeymapMode::Input
+    }
+
+    if app.edit_mode.inserting() {
+        KeymapMode::Input
+    } else if app.navi_index.show() {
+        KeymapMode::Navi
+    } else if app.edit_mode.enabled {
+        KeymapMode::Edit
+    } else {
+        KeymapMode::Normal
+    }
+}
+
+fn in_root(app: &App) -> bool {
+    match app.selected_block {
+        Block::Browser(in_root) => in_root,
+        _ => app.root()
+    }
+}
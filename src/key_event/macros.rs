@@ -0,0 +1,200 @@
+// Keyboard Macros
+
+use std::collections::HashMap;
+
+use anyhow::bail;
+use ratatui::DefaultTerminal;
+use ratatui::text::{Line, Text};
+use toml_edit::{value, DocumentMut, InlineTable};
+
+use super::{SwitchCase, SwitchCaseData};
+
+use crate::app::App;
+use crate::rt_error;
+use crate::utils::CmdContent;
+use crate::error::AppResult;
+use crate::config::{get_document, write_document, Key};
+
+/// The keys recorded into registers.
+#[derive(Default)]
+pub struct MacroRecorder {
+    registers: HashMap<char, Vec<Key>>,
+
+    /// The register being recorded.
+    recording: Option<char>,
+    keys: Vec<Key>,
+
+    /// Keys of the incomplete key sequence, they're dropped when the sequence stops recording.
+    buffer: Vec<Key>,
+
+    playing: bool,
+
+    /// The register chosen to play, which is played with terminal later.
+    pending_play: Option<char>,
+}
+
+impl MacroRecorder {
+    pub fn recording(&self) -> Option<char> {
+        self.recording
+    }
+
+    /// Record KEY, COMPLETE refers to whether a key sequence is finished.
+    pub fn record(&mut self, key: Key, complete: bool) {
+        if self.recording.is_none() {
+            self.buffer.clear();
+            return
+        }
+
+        self.buffer.push(key);
+        if complete {
+            self.keys.append(&mut self.buffer);
+        }
+    }
+}
+
+/// Start recording macro, or stop it if it's being recorded.
+pub fn macro_record(app: &mut App) -> AppResult<()> {
+    if let Some(register) = app.macros.recording.take() {
+        app.macros.buffer.clear();
+        let keys = std::mem::take(&mut app.macros.keys);
+        if !keys.is_empty() {
+            save_macro(app, register, keys)?;
+        }
+
+        return Ok(())
+    }
+
+    SwitchCase::new(
+        app,
+        record_switch,
+        false,
+        CmdContent::Text(Text::raw("Press a letter or digit as register to record macro")),
+        SwitchCaseData::None
+    );
+
+    Ok(())
+}
+
+fn record_switch(app: &mut App, key: char, _: SwitchCaseData) -> AppResult<bool> {
+    if !key.is_ascii_alphanumeric() {
+        rt_error!("The register of macro should be a letter or digit")
+    }
+
+    app.macros.recording = Some(key);
+    app.macros.keys.clear();
+
+    Ok(true)
+}
+
+/// Play the macro in REGISTER, ask for the register when it's None.
+pub fn macro_play(
+    app: &mut App,
+    terminal: &mut DefaultTerminal,
+    register: Option<char>
+) -> AppResult<()>
+{
+    if let Some(register) = register {
+        return play(app, terminal, register)
+    }
+
+    let mut registers = app.macros.registers.iter().collect::<Vec<_>>();
+    registers.sort_by_key(|(register, _)| **register);
+
+    let mut msg = Text::raw("Press the register of macro to play");
+    msg.push_line("");
+    for (register, keys) in registers.into_iter() {
+        msg.push_line(Line::raw(format!(
+            "[{}] {}",
+            register,
+            keys.iter().map(Key::to_string).collect::<String>()
+        )));
+    }
+
+    SwitchCase::new(
+        app,
+        play_switch,
+        true,
+        CmdContent::Text(msg),
+        SwitchCaseData::None
+    );
+
+    Ok(())
+}
+
+fn play_switch(app: &mut App, key: char, _: SwitchCaseData) -> AppResult<bool> {
+    // The macro is played after quitting the switch case, where the terminal is available.
+    app.macros.pending_play = Some(key);
+
+    Ok(true)
+}
+
+/// Play the macro chosen in `macro_play`.
+pub fn run_pending_play(app: &mut App, terminal: &mut DefaultTerminal) -> AppResult<()> {
+    if let Some(register) = app.macros.pending_play.take() {
+        play(app, terminal, register)?;
+    }
+
+    Ok(())
+}
+
+fn play(app: &mut App, terminal: &mut DefaultTerminal, register: char) -> AppResult<()> {
+    if app.macros.playing {
+        rt_error!("Cannot play macro inside another macro")
+    }
+
+    let keys = if let Some(keys) = app.macros.registers.get(&register) {
+        keys.to_owned()
+    } else {
+        rt_error!(format!("There's no macro in register {}", register))
+    };
+
+    app.macros.playing = true;
+    let result = keys.into_iter()
+        .try_for_each(|key| super::handle_key(key.into(), app, terminal));
+    app.macros.playing = false;
+
+    result
+}
+
+fn save_macro(app: &mut App, register: char, keys: Vec<Key>) -> AppResult<()> {
+    let mut toml_config = get_document(app.config_path.to_owned())?;
+    if toml_config.get("macros").is_none() {
+        toml_config["macros"] = value(InlineTable::new());
+    }
+    toml_config["macros"][register.to_string().as_str()] = value(
+        keys.iter().map(Key::to_string).collect::<String>()
+    );
+
+    write_document(toml_config)?;
+    app.macros.registers.insert(register, keys);
+
+    Ok(())
+}
+
+/// Read config for macros.
+pub fn read_config(app: &mut App, document: &DocumentMut) -> anyhow::Result<()> {
+    if let Some(item) = document.get("macros") {
+        let table = if let Some(table) = item.as_table_like() {
+            table
+        } else {
+            bail!("Wrong type for macros config")
+        };
+
+        for (register, keys) in table.iter() {
+            let mut chars = register.chars();
+            let (Some(register), None) = (chars.next(), chars.next()) else {
+                bail!("The register of macro should be a single character")
+            };
+
+            let keys = if let Some(keys) = keys.as_str() {
+                Key::parse_sequence(keys)?
+            } else {
+                bail!("Wrong type for macros config")
+            };
+
+            app.macros.registers.insert(register, keys);
+        }
+    }
+
+    Ok(())
+}
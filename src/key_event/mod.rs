@@ -1,16 +1,28 @@
 // Key Event
 
+mod job;
 mod tab;
 mod edit;
 mod shell;
+mod trash;
+mod archive;
+mod bulk_rename;
+mod journal;
+mod macros;
+mod history_search;
+mod key_dispatch;
+mod dir_watcher;
 mod switch;
-mod interaction;
+mod grep;
+mod finder;
 mod file_search;
+mod sort_operation;
 mod command_line;
 mod goto_operation;
 mod cursor_movement;
 mod file_operations;
 mod paste_operation;
+mod permissions;
 mod simple_operations;
 
 use std::ops::SubAssign;
@@ -20,8 +32,8 @@ use ratatui::DefaultTerminal;
 use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use tab::tab_operation;
-use interaction::fzf_jump;
 use goto_operation::goto_operation;
+use sort_operation::sort_operation;
 use paste_operation::paste_operation;
 use cursor_movement::{directory_movement, jump_to_index};
 use file_operations::{append_file_name, delete_operation, mark_operation};
@@ -33,8 +45,28 @@ use crate::command::AppCommand;
 use crate::utils::{delete_word, Block, CmdContent, CursorPos, Direction};
 
 // Export
-pub use tab::TabList;
-pub use file_search::FileSearcher;
+pub use tab::{TabList, tab_command};
+pub use file_search::{FileSearcher, FileFilter, SearchMode};
+pub use dir_watcher::DirWatcher;
+pub use archive::{
+    ArchiveCache,
+    compress,
+    read_entry,
+    split_archive_path,
+    archive_entry_path,
+};
+pub use sort_operation::SortConfig;
+pub use journal::{Journal, FsOperation};
+pub use macros::MacroRecorder;
+pub use finder::Finder;
+pub use bulk_rename::bulk_rename;
+pub use permissions::{chmod, chown};
+pub use grep::{GrepSearch, grep};
+pub use history_search::HistorySearch;
+pub use key_dispatch::{PendingKeys, pending_keys_timeout};
+pub use job::{JobManager, cancel_jobs};
+pub use paste_operation::{ConflictPolicy, ConflictResolver, create_files};
+pub use trash::{list_trash, restore_trash, empty_trash, move_to_trash};
 pub use edit::{EditMode, EditItem};
 pub use simple_operations::output_path;
 pub use switch::{SwitchCase, SwitchCaseData};
@@ -45,6 +77,8 @@ pub use shell::{ShellCommand, CommandStr, shell_process, fetch_working_directory
 // Export for auto config
 pub use tab::read_config as tab_read_config;
 pub use goto_operation::read_config as goto_read_config;
+pub use sort_operation::read_config as sort_read_config;
+pub use macros::read_config as macro_read_config;
 
 // NOTE(for coding): When quiting command-line mode, you're required to use quit_command_mode function!
 // NOTE(for coding): DO NOT use return in the match control to skip specific code, which
@@ -56,46 +90,50 @@ pub fn handle_event(
     terminal: &mut DefaultTerminal
 ) -> AppResult<()>
 {
+    let recording = app.macros.recording().is_some();
+    let result = handle_key(key, app, terminal);
+
+    // The key stopping recording is not recorded.
+    if recording {
+        app.macros.record(key.into(), app.pending_keys.is_empty());
+    }
+
+    result
+}
+
+/// Handle KEY event without recording it into macro.
+fn handle_key(
+    key: KeyEvent,
+    app: &mut App,
+    terminal: &mut DefaultTerminal
+) -> AppResult<()>
+{
+    // Keys are inputted into finder when it's opened.
+    if finder::handle_key(key, app)? {
+        return Ok(())
+    }
+
+    // The results of grep are navigated until they're hidden.
+    if grep::handle_key(key, app)? {
+        return Ok(())
+    }
+
+    // Reverse search of history takes over the command line.
+    if history_search::handle_key(key, app) {
+        return Ok(())
+    }
+
+    // Keys bound in keymap take precedence over the built-in behaviours.
+    if key_dispatch::dispatch_key(key, app, terminal)? {
+        return Ok(())
+    }
+
     match key.code {
         KeyCode::Char(c) => {
-            // Handle keys with modifiers
+            // Keys with modifiers are only available in keymap.
             if !(key.modifiers.is_empty() ||
                  key.modifiers == KeyModifiers::SHIFT)
             {
-                match key.modifiers {
-                    KeyModifiers::CONTROL => {
-                        match c {
-                            'g' => app.command_completion.hide(),
-                            'b' | 'a' => {
-                                if app.edit_mode.inserting() {
-                                    app.edit_mode.cursor_move(false, c == 'a')?;
-                                } else {
-                                    app.cursor_left(c == 'a');
-                                }
-                            },
-                            'f' | 'e' => {
-                                if app.edit_mode.inserting() {
-                                    app.edit_mode.cursor_move(true, c == 'e')?;
-                                } else {
-                                    app.cursor_right(c == 'e');
-                                }
-                            },
-
-                            'n' | 'p' => {
-                                if app.command_completion.show_frame() {
-                                    command_line::switch_to(app, c == 'n')?;
-                                } else {
-                                    app.command_select(c == 'n');
-                                }
-                            },
-
-                            _ => ()
-                        }
-                    },
-
-                    _ => ()
-                }
-
                 return Ok(())
             }
 
@@ -109,25 +147,19 @@ pub fn handle_event(
             if let Some(ref case) = app.switch_case {
                 let case = case.to_owned();
                 switch::switch_match(app, case, c)?;
+                shell::run_pending_open(app, terminal)?;
+                macros::run_pending_play(app, terminal)?;
                 return Ok(())
             }
 
-            // Execute keybinding or insert character.
-            if let Block::Browser(in_root) = app.selected_block {
+            // Insert character, the keybindings have been executed before.
+            if let Block::Browser(_) = app.selected_block {
                 if app.edit_mode.inserting() {
                     app.edit_mode.insert_char(c);
                     return Ok(())
                 }
 
-                let command = if app.navi_index.show() {
-                    app.keymap.navi_get(c)?
-                } else if app.edit_mode.enabled {
-                    app.keymap.edit_get(c)?
-                } else {
-                    app.keymap.get(c)?
-                };
-
-                command.execute(app, terminal, in_root)?;
+                rt_error!("Invalid keybinding")
             } else {
                 app.command_line_append(c);
             }
@@ -232,79 +264,6 @@ pub fn handle_event(
             }
         },
 
-        KeyCode::Enter => {
-            if app.command_error {
-                app.quit_command_mode();
-                return Ok(())
-            } else {
-                if let Block::CommandLine(_, ref _cursor) = app.selected_block {
-                    if *_cursor != CursorPos::None {
-                        app.command_parse(terminal)?;
-                    }
-
-                    return Ok(())
-                }
-            }
-
-            if app.navi_index.show() {
-                jump_to_index(app)?;
-                return Ok(())
-            }
-
-            if app.edit_mode.enabled {
-                // Avoid unexpected exit when editing files.
-                if app.edit_mode.inserting() {
-                    return Ok(())
-                }
-
-                SwitchCase::new(
-                    app,
-                    edit::save_edit,
-                    false,
-                    edit::generate_msg(),
-                    SwitchCaseData::None
-                );
-            }
-
-            output_path(app, false)?;
-        },
-
-        KeyCode::Up => {
-            if let Block::CommandLine(_, _) = app.selected_block {
-                if app.command_expand {
-                    app.expand_scroll(Direction::Up);
-                } else {
-                    app.command_select(false);
-                }
-            }
-        },
-
-        KeyCode::Down => {
-            if let Block::CommandLine(_, _) = app.selected_block {
-                if app.command_expand {
-                    app.expand_scroll(Direction::Down);
-                } else {
-                    app.command_select(true);
-                }
-            }
-        },
-
-        KeyCode::Left => {
-            if app.command_expand && key.modifiers == KeyModifiers::ALT {
-                app.expand_scroll(Direction::Left);
-                return Ok(())
-            }
-            app.cursor_left(false)
-        },
-
-        KeyCode::Right => {
-            if app.command_expand && key.modifiers == KeyModifiers::ALT {
-                app.expand_scroll(Direction::Right);
-                return Ok(())
-            }
-            app.cursor_right(false)
-        },
-
         KeyCode::Tab => {
             match key.modifiers {
                 KeyModifiers::ALT => {
@@ -333,6 +292,44 @@ pub fn handle_event(
     Ok(())
 }
 
+/// Confirm the command line, navigation index or edit mode.
+fn confirm(app: &mut App, terminal: &mut DefaultTerminal) -> AppResult<()> {
+    if app.command_error {
+        app.quit_command_mode();
+        return Ok(())
+    } else {
+        if let Block::CommandLine(_, ref _cursor) = app.selected_block {
+            if *_cursor != CursorPos::None {
+                app.command_parse(terminal)?;
+            }
+
+            return Ok(())
+        }
+    }
+
+    if app.navi_index.show() {
+        jump_to_index(app)?;
+        return Ok(())
+    }
+
+    if app.edit_mode.enabled {
+        // Avoid unexpected exit when editing files.
+        if app.edit_mode.inserting() {
+            return Ok(())
+        }
+
+        SwitchCase::new(
+            app,
+            edit::save_edit,
+            false,
+            edit::generate_msg(),
+            SwitchCaseData::None
+        );
+    }
+
+    output_path(app, false)
+}
+
 impl AppCommand {
     pub fn execute(
         self,
@@ -344,14 +341,18 @@ impl AppCommand {
         match self {
             AppCommand::Tab             => tab_operation(app)?,
             AppCommand::Goto            => goto_operation(app),
+            AppCommand::Sort(key)       => sort_operation(app, key)?,
             AppCommand::Paste           => paste_operation(app)?,
-            AppCommand::Delete          => delete_operation(app),
+            AppCommand::Delete          => delete_operation(app, false),
             AppCommand::ShowNaviIndex   => app.navi_index.init(),
             AppCommand::OutputFile      => output_path(app, true)?,
             AppCommand::MarkExpand      => app.mark_expand = true,
             AppCommand::EditDelete      => edit::mark_delete(app)?,
             AppCommand::HideOrShow      => app.hide_or_show(None)?,
-            AppCommand::FzfJump         => fzf_jump(app, terminal)?,
+            AppCommand::IgnoreOrShow    => app.ignore_or_show()?,
+            AppCommand::DirSize         => app.compute_dir_size()?,
+            AppCommand::DiskUsage       => app.toggle_disk_usage()?,
+            AppCommand::Finder          => finder::open_finder(app),
             AppCommand::CmdShell        => shell::cmdline_shell(app)?,
             AppCommand::PrintFullPath   => simple_operations::print_full_path(app),
             AppCommand::SingleSymlink   => paste_operation::make_single_symlink(app)?,
@@ -359,6 +360,23 @@ impl AppCommand {
             AppCommand::QuitAfterOutput => app.quit_after_output = !app.quit_after_output,
             AppCommand::NextTab         => {tab::next(app)?;},
             AppCommand::PrevTab         => {tab::prev(app)?;},
+            AppCommand::PermanentDelete => delete_operation(app, true),
+            AppCommand::Undo            => journal::undo(app)?,
+            AppCommand::Redo            => journal::redo(app)?,
+            AppCommand::JobList         => job::job_list(app)?,
+            AppCommand::OpenWith        => shell::open_with(app)?,
+            AppCommand::Confirm         => confirm(app, terminal)?,
+            AppCommand::HideCompletion  => app.command_completion.hide(),
+            AppCommand::HistorySearch   => history_search::history_search(app)?,
+            AppCommand::MacroRecord     => macros::macro_record(app)?,
+
+            AppCommand::MacroPlay(register) => macros::macro_play(app, terminal, register)?,
+
+            AppCommand::Chain(commands) => {
+                for command in commands.into_iter() {
+                    command.execute(app, terminal, app.root())?;
+                }
+            },
 
             AppCommand::NaviIndexInput(idx)   => app.navi_index.input(idx),
             AppCommand::SwitchTab(idx)        => tab::quick_switch(app, idx)?,
@@ -417,6 +435,37 @@ impl AppCommand {
                 true
             )?,
 
+            AppCommand::CursorMove(right, edge) => {
+                if app.edit_mode.inserting() {
+                    app.edit_mode.cursor_move(right, edge)?;
+                } else if right {
+                    app.cursor_right(edge);
+                } else {
+                    app.cursor_left(edge);
+                }
+            },
+
+            AppCommand::SelectCandidate(next) => {
+                if app.command_completion.show_frame() {
+                    command_line::switch_to(app, next)?;
+                } else {
+                    app.command_select(next);
+                }
+            },
+
+            AppCommand::CommandScroll(direction) => {
+                if app.command_expand {
+                    app.expand_scroll(direction);
+                } else {
+                    match direction {
+                        Direction::Up    => app.command_select(false),
+                        Direction::Down  => app.command_select(true),
+                        Direction::Left  => app.cursor_left(false),
+                        Direction::Right => app.cursor_right(false),
+                    }
+                }
+            },
+
             AppCommand::ItemMove(direction) => directory_movement(
                 direction,
                 app,
@@ -469,6 +518,28 @@ impl AppCommand {
                 app.prev_candidate()?
             },
 
+            AppCommand::SearchMode(mode) => app.search_mode(mode),
+
+            AppCommand::Filter => {
+                // Edit the filter of current directory if there's one.
+                let input = app.file_filter.as_ref()
+                    .filter(|filter| filter.applies(&app.path))
+                    .map(|filter| filter.input().to_owned())
+                    .unwrap_or_default();
+
+                app.selected_block.set_command_line(
+                    format!(":filter {}", input),
+                    CursorPos::End
+                )
+            },
+
+            AppCommand::FilterClear => app.set_filter(None)?,
+
+            AppCommand::BulkRename => app.selected_block.set_command_line(
+                ":bulk_rename ",
+                CursorPos::End
+            ),
+
             AppCommand::WorkDirectory(set) => if set {
                 shell::set_working_directory(
                     app.path.to_owned()
@@ -1,15 +1,22 @@
 // Paste operation.
 
 use std::fs;
-use std::io::ErrorKind;
+use std::mem::take;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::io::{self, ErrorKind, Read, Write};
 
-use super::SwitchCase;
-use super::file_operations::delete_file;
+use chrono::{DateTime, Local};
+
+use super::switch::SwitchStruct;
+use super::{FsOperation, SwitchCase, SwitchCaseData};
+use super::trash::move_to_trash;
+use super::archive::{archive_entry_path, entry_size, extract_entry};
+use super::job::{JobContext, JobKind, JobResult};
 
 use crate::{rt_error, App};
-use crate::utils::{CmdContent, CursorPos, MarkedFiles};
+use crate::command::quote_arg;
+use crate::utils::{unique_path, CmdContent, CursorPos, MarkedFiles};
 use crate::error::{
     NotFoundType,
     ErrorType,
@@ -17,13 +24,121 @@ use crate::error::{
     AppError,
 };
 
-macro_rules! append_error {
-    ($errors:expr, $x:expr) => {
-        let (_, _errs) = $x;
-        if !_errs.is_empty() {
-            $errors.append_errors(_errs.iter());
+/// The size of buffer used when copying a file.
+const COPY_BUFFER_SIZE: usize = 64 * 1024;
+
+/// How to handle the file whose name already exists in target directory.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Move the existing file to trash.
+    Overwrite,
+    Skip,
+
+    /// Paste with a new name by adding number suffix.
+    Rename,
+
+    /// Overwrite only when the pasted file is newer than the existing one.
+    KeepNewer,
+}
+
+/// The policies of conflicted files for a pasting.
+#[derive(Clone)]
+pub struct ConflictResolver {
+    /// The policy for the files without specific one.
+    default: ConflictPolicy,
+    policies: HashMap<PathBuf, ConflictPolicy>,
+}
+
+/// The state of pasting while resolving conflicts.
+#[derive(Clone)]
+struct PasteState {
+    moving: bool,
+    target: PathBuf,
+    files: HashMap<PathBuf, MarkedFiles>,
+    resolver: ConflictResolver,
+
+    /// The original files conflicting with target, the last one is resolved first.
+    conflicts: Vec<PathBuf>,
+    total_conflicts: usize,
+}
+
+/// The state of creating files while resolving conflicts with existing ones.
+#[derive(Clone)]
+struct CreateState {
+    names: Vec<String>,
+    is_dir: bool,
+    resolver: ConflictResolver,
+
+    /// The existing files conflicting with new ones, the last one is resolved first.
+    conflicts: Vec<PathBuf>,
+    total_conflicts: usize,
+}
+
+impl ConflictPolicy {
+    /// Turn KeepNewer into the real action according to modified time.
+    fn apply(self, origin: &Path, existing: &fs::Metadata) -> Self {
+        if self != ConflictPolicy::KeepNewer {
+            return self
         }
-    };
+
+        let origin_time = fs::metadata(origin).and_then(|metadata| metadata.modified());
+        match (origin_time, existing.modified()) {
+            (Ok(origin_time), Ok(existing_time)) if origin_time > existing_time => {
+                ConflictPolicy::Overwrite
+            },
+            _ => ConflictPolicy::Skip
+        }
+    }
+}
+
+impl ConflictResolver {
+    pub fn new(default: ConflictPolicy) -> Self {
+        Self { default, policies: HashMap::new() }
+    }
+
+    pub fn get(&self, origin: &Path) -> ConflictPolicy {
+        *self.policies.get(origin).unwrap_or(&self.default)
+    }
+}
+
+impl PasteState {
+    fn new(files: HashMap<PathBuf, MarkedFiles>, target: PathBuf, moving: bool) -> Self {
+        let mut conflicts: Vec<PathBuf> = Vec::new();
+        for (path, files) in files.iter() {
+            for name in files.files.keys() {
+                let origin = path.join(name);
+                let target_file = target.join(name);
+
+                if target_file != origin && target_file.symlink_metadata().is_ok() {
+                    conflicts.push(origin);
+                }
+            }
+        }
+
+        conflicts.sort();
+        conflicts.reverse();
+
+        Self {
+            moving,
+            target,
+            files,
+            total_conflicts: conflicts.len(),
+            conflicts,
+            resolver: ConflictResolver::new(ConflictPolicy::Rename),
+        }
+    }
+}
+
+impl SwitchStruct for PasteState {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl SwitchStruct for CreateState {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
 }
 
 pub fn paste_operation(app: &mut App) -> AppResult<()> {
@@ -36,26 +151,40 @@ pub fn paste_operation(app: &mut App) -> AppResult<()> {
         paste_switch,
         true,
         generate_msg(app),
-        super::SwitchCaseData::None
+        SwitchCaseData::None
     );
 
     Ok(())
 }
 
 /// Paste files and return failed files & errors.
+/// The succeeded operations will be pushed into OPERATIONS.
+/// When MOVING is true, files are renamed directly if they're on the same device.
 pub fn paste_files<'a, I, P>(file_iter: I,
                              target_path: P,
-                             overwrite: bool
+                             resolver: &ConflictResolver,
+                             moving: bool,
+                             operations: &mut Vec<FsOperation>,
+                             context: &mut JobContext
 ) -> (HashMap<PathBuf, Vec<String>>, AppError)
 where
-    I: Iterator<Item = (&'a PathBuf, &'a MarkedFiles)>,
+    I: Iterator<Item = (&'a PathBuf, &'a MarkedFiles)> + Clone,
     P: AsRef<Path>
 {
-    use copy_dir::copy_dir;
-
     let mut errors = AppError::new();
     let mut failed_files: HashMap<PathBuf, Vec<String>> = HashMap::new();
 
+    // Count the total size for progress.
+    let (mut total_files, mut total_bytes) = (0, 0);
+    for (path, files) in file_iter.clone() {
+        for file in files.files.keys() {
+            let (_files, _bytes) = item_size(&path.join(file));
+            total_files += _files;
+            total_bytes += _bytes;
+        }
+    }
+    context.set_total(total_files, total_bytes);
+
     macro_rules! file_action {
         ($func:expr, $path: expr, $file:expr, $from:expr $(, $to:expr )*) => {
             match $func($from, $( $to )*) {
@@ -67,20 +196,26 @@ where
                     errors.add_error(err);
                     continue;
                 },
-                Ok(_) => ()
+                Ok(_result) => _result
             }
         }
     }
 
     for (path, files) in file_iter {
-        let mut target_exists = false;
-        let mut target_is_dir = false;
-
         for file in files.files.iter() {
+            // Keep the rest files when the job is cancelled.
+            if context.cancelled() {
+                failed_files.entry(path.to_owned())
+                    .or_default()
+                    .push(file.0.to_owned());
+                continue;
+            }
+
+            let origin_path = path.join(file.0);
             let mut final_path = target_path.as_ref().join(file.0);
-            let target_file = fs::metadata(&final_path);
+
             // Check whether the target file exists.
-            match target_file {
+            match fs::symlink_metadata(&final_path) {
                 Err(err) => {
                     match err.kind() {
                         ErrorKind::NotFound => (), // Nice find.
@@ -90,64 +225,228 @@ where
                     }
                 },
                 Ok(metadata) => {
-                    if !overwrite {
-                        final_path = target_path.as_ref().join(
-                            format!("{}.new", file.0)
-                        );
-                        // exists_files
-                        //     .entry(path.to_owned())
-                        //     .or_insert(Vec::new())
-                        //     .push(file.0.to_owned());
+                    // Pasting a file into its own directory can only make a copy with new name.
+                    let policy = if final_path == origin_path {
+                        ConflictPolicy::Rename
                     } else {
-                        target_exists = true;
-                        target_is_dir = metadata.is_dir();
+                        resolver.get(&origin_path).apply(&origin_path, &metadata)
+                    };
+
+                    match policy {
+                        ConflictPolicy::Skip => {
+                            // The skipped files are regarded as failed ones to keep their origin.
+                            failed_files.entry(path.to_owned())
+                                .or_default()
+                                .push(file.0.to_owned());
+
+                            let (_files, _bytes) = item_size(&origin_path);
+                            context.add_progress(_files, _bytes);
+                            continue;
+                        },
+
+                        ConflictPolicy::Rename => final_path = unique_path(final_path),
+
+                        _ => {
+                            // A directory is never overwritten by a file, and vice versa.
+                            if metadata.is_dir() != *file.1 {
+                                failed_files.entry(path.to_owned())
+                                    .or_default()
+                                    .push(file.0.to_owned());
+
+                                errors.add_error(anyhow::anyhow!(
+                                    "Cannot overwrite {} with a {}",
+                                    final_path.to_string_lossy(),
+                                    if *file.1 { "directory" } else { "file" }
+                                ));
+
+                                let (_files, _bytes) = item_size(&origin_path);
+                                context.add_progress(_files, _bytes);
+                                continue;
+                            }
+
+                            // The overwritten file is moved to trash, so that it can be restored by undo.
+                            let entry = file_action!(
+                                move_to_trash,
+                                path,
+                                file,
+                                &final_path
+                            );
+                            operations.push(FsOperation::Trashed(entry));
+                        }
                     }
                 }
             }
 
-            // NOTE: If the exists_file is a directory and the original one is not, cancel this action
-            // and vice versa.
-            if target_exists {
-                if target_is_dir {
-                    file_action!(
-                        fs::remove_dir_all,
-                        path,
-                        file,
-                        target_path.as_ref().join(&file.0)
-                    );
-                } else {
-                    file_action!(
-                        fs::remove_file,
-                        path,
-                        file,
-                        target_path.as_ref().join(&file.0)
-                    );
+            // Entries inside archive are extracted, the archive itself is not modified.
+            if let Some((archive, inner)) = archive_entry_path(&origin_path) {
+                if let Err(err) = extract_entry(&archive, &inner, &final_path, context) {
+                    if *file.1 {
+                        let _ = fs::remove_dir_all(&final_path);
+                    } else {
+                        let _ = fs::remove_file(&final_path);
+                    }
+
+                    failed_files.entry(path.to_owned())
+                        .or_default()
+                        .push(file.0.to_owned());
+
+                    if err.kind() != ErrorKind::Interrupted {
+                        errors.add_error(err);
+                    }
+                    continue;
                 }
+
+                operations.push(FsOperation::Created(final_path));
+                continue;
             }
 
-            if *file.1 {         // The original file is a dir.
-                file_action!(
-                    copy_dir,
-                    path,
-                    file,
-                    path.join(&file.0),
-                    final_path
-                );
-            } else {
-                file_action!(
-                    fs::copy,
-                    path,
-                    file,
-                    path.join(&file.0),
-                    final_path
-                );
+            if moving {
+                match fs::rename(&origin_path, &final_path) {
+                    Ok(_) => {
+                        let (_files, _bytes) = item_size(&final_path);
+                        context.add_progress(_files, _bytes);
+                        operations.push(FsOperation::Renamed(origin_path, final_path));
+                        continue;
+                    },
+                    Err(err) if err.kind() != ErrorKind::CrossesDevices => {
+                        failed_files.entry(path.to_owned())
+                            .or_default()
+                            .push(file.0.to_owned());
+
+                        errors.add_error(err);
+                        continue;
+                    },
+                    Err(_) => ()
+                }
             }
+
+            if let Err(err) = copy_item(&origin_path, &final_path, *file.1, context) {
+                // Remove the partial copied file.
+                if *file.1 {
+                    let _ = fs::remove_dir_all(&final_path);
+                } else {
+                    let _ = fs::remove_file(&final_path);
+                }
+
+                failed_files.entry(path.to_owned())
+                    .or_default()
+                    .push(file.0.to_owned());
+
+                if err.kind() != ErrorKind::Interrupted {
+                    errors.add_error(err);
+                }
+                continue;
+            }
+
+            operations.push(FsOperation::Copied(origin_path, final_path));
         }
     }
 
+    if context.cancelled() {
+        errors.add_error(anyhow::anyhow!(
+            "Pasting to {} is cancelled",
+            target_path.as_ref().to_string_lossy()
+        ));
+    }
+
     (failed_files, errors)
 }
 
+/// Copy FROM to TO and report the progress to CONTEXT.
+/// Symbolic links inside directories are copied as links.
+fn copy_item(from: &Path,
+             to: &Path,
+             is_dir: bool,
+             context: &mut JobContext
+) -> io::Result<()>
+{
+    if !is_dir {
+        copy_file(from, to, context)?;
+        fs::set_permissions(to, fs::metadata(from)?.permissions())?;
+        context.add_progress(1, 0);
+
+        return Ok(())
+    }
+
+    fs::create_dir(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+
+        if file_type.is_symlink() {
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, target)?;
+            context.add_progress(1, 0);
+        } else {
+            copy_item(&entry.path(), &target, file_type.is_dir(), context)?;
+        }
+    }
+
+    fs::set_permissions(to, fs::metadata(from)?.permissions())?;
+    context.add_progress(1, 0);
+
+    Ok(())
+}
+
+fn copy_file(from: &Path, to: &Path, context: &mut JobContext) -> io::Result<()> {
+    let mut reader = fs::File::open(from)?;
+    let mut writer = fs::File::create(to)?;
+
+    copy_stream(&mut reader, &mut writer, context)
+}
+
+/// Copy the data from READER to WRITER and report the progress to CONTEXT.
+pub fn copy_stream<R, W>(reader: &mut R, writer: &mut W, context: &mut JobContext) -> io::Result<()>
+where
+    R: Read + ?Sized,
+    W: Write + ?Sized
+{
+    let mut buffer = vec![0; COPY_BUFFER_SIZE];
+
+    loop {
+        if context.cancelled() {
+            return Err(io::Error::new(ErrorKind::Interrupted, "The job is cancelled"))
+        }
+
+        let size = reader.read(&mut buffer)?;
+        if size == 0 {
+            break;
+        }
+
+        writer.write_all(&buffer[..size])?;
+        context.add_progress(0, size as u64);
+    }
+
+    Ok(())
+}
+
+/// Get the number of files & bytes inside PATH.
+pub fn item_size(path: &Path) -> (usize, u64) {
+    let metadata = if let Ok(metadata) = fs::symlink_metadata(path) {
+        metadata
+    } else {
+        return archive_entry_path(path)
+            .map(|(archive, inner)| entry_size(&archive, &inner))
+            .unwrap_or((0, 0))
+    };
+
+    if !metadata.is_dir() {
+        return (1, metadata.len())
+    }
+
+    let (mut files, mut bytes) = (1, 0);
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let (_files, _bytes) = item_size(&entry.path());
+            files += _files;
+            bytes += _bytes;
+        }
+    }
+
+    (files, bytes)
+}
+
 pub fn make_single_symlink(app: &mut App) -> AppResult<()> {
     if app.marked_files.is_empty() {
         return Err(ErrorType::NoSelected.pack())
@@ -163,8 +462,8 @@ pub fn make_single_symlink(app: &mut App) -> AppResult<()> {
             app.selected_block.set_command_line(
                 format!(
                     ":create_symlink {} {}",
-                    original_file.to_string_lossy(),
-                    app.current_path().join(file).to_string_lossy()
+                    quote_arg(&original_file.to_string_lossy()),
+                    quote_arg(&app.current_path().join(file).to_string_lossy())
                 ),
                 CursorPos::End
             );
@@ -178,35 +477,26 @@ pub fn make_single_symlink(app: &mut App) -> AppResult<()> {
 fn paste_switch(
     app: &mut App,
     key: char,
-    _: super::SwitchCaseData
+    _: SwitchCaseData
 ) -> AppResult<bool>
 {
-    let mut errors = AppError::new();
     let current_dir = app.current_path();
     let files = app.marked_files.to_owned();
 
     match key {
-        'p' => {
-            let (failed_files, mut _errs) = paste_files(
-                files.iter(),
-                current_dir,
-                false
-            );
-
-            if !_errs.is_empty() {
-                errors.append_errors(_errs.iter());
-            }
-            
-            if let Err(err) = remove_origin_files(
-                app,
-                files.into_iter(),
-                failed_files
-            )
-            {
-                errors.append_errors(err.iter());
-            }
+        'p' | 'c' => {
+            let state = PasteState::new(files, current_dir, key == 'p');
+            return resolve_conflicts(app, state)
         },
 
+        'o' | 'O' => paste_job(
+            app,
+            files,
+            current_dir,
+            ConflictResolver::new(ConflictPolicy::Overwrite),
+            key == 'O'
+        )?,
+
         's' => {
             let mut final_files: Vec<(PathBuf, PathBuf)> = Vec::new();
             for (path, files) in files.into_iter() {
@@ -218,43 +508,6 @@ fn paste_switch(
             crate::command::create_symlink(app, final_files.into_iter())?;
         },
 
-        'c' => {
-            append_error!(errors, paste_files(
-                files.iter(),
-                current_dir,
-                false
-            ));
-        },
-
-        'o' => {
-            append_error!(errors, paste_files(
-                files.iter(),
-                current_dir,
-                true
-            ));
-        },
-
-        'O' => {
-            let (failed_files, _errs) = paste_files(
-                files.iter(),
-                current_dir,
-                true
-            );
-
-            if !_errs.is_empty() {
-                errors.append_errors(_errs.iter());
-            }
-
-            if let Err(err) = remove_origin_files(
-                app,
-                files.into_iter(),
-                failed_files
-            )
-            {
-                errors.append_errors(err.iter());
-            }
-        },
-
         'x' => (),
         _ => {
             return Err(
@@ -267,49 +520,311 @@ fn paste_switch(
 
     restore_status(app)?;
 
-    if !errors.is_empty() {
-        return Err(errors)
+    Ok(true)
+}
+
+/// Ask the user how to handle conflicted files one by one, then start pasting.
+fn resolve_conflicts(app: &mut App, state: PasteState) -> AppResult<bool> {
+    if let Some(conflict) = state.conflicts.last() {
+        SwitchCase::new(
+            app,
+            conflict_switch,
+            true,
+            conflict_msg(&state, conflict),
+            SwitchCaseData::Struct(Box::new(state))
+        );
+
+        return Ok(false)
     }
 
+    paste_job(app, state.files, state.target, state.resolver, state.moving)?;
+    restore_status(app)?;
+
     Ok(true)
 }
 
-fn remove_origin_files<I>(
+fn conflict_switch(
+    app: &mut App,
+    key: char,
+    data: SwitchCaseData
+) -> AppResult<bool>
+{
+    let mut state = if let SwitchCaseData::Struct(data) = data {
+        match data.as_any().downcast_ref::<PasteState>() {
+            Some(state) => state.to_owned(),
+            None => panic!("Unknow panic occurred at conflict_switch in paste_operation.rs!"),
+        }
+    } else {
+        panic!("Unexpected situation at conflict_switch in paste_operation.rs.")
+    };
+
+    if key.eq_ignore_ascii_case(&'x') {
+        return Ok(true)
+    }
+
+    if !choose_policy(key, &mut state.resolver, &mut state.conflicts, true) {
+        return Ok(false)
+    }
+
+    resolve_conflicts(app, state)
+}
+
+/// Set the policy of KEY for the last one of CONFLICTS,
+/// uppercase keys apply the policy to all the rest conflicts.
+/// Return false when KEY is not a policy.
+fn choose_policy(key: char,
+                 resolver: &mut ConflictResolver,
+                 conflicts: &mut Vec<PathBuf>,
+                 keep_newer: bool
+) -> bool
+{
+    let policy = match key.to_ascii_lowercase() {
+        'o' => ConflictPolicy::Overwrite,
+        's' => ConflictPolicy::Skip,
+        'r' => ConflictPolicy::Rename,
+        'n' if keep_newer => ConflictPolicy::KeepNewer,
+        _ => return false
+    };
+
+    if key.is_ascii_uppercase() {
+        resolver.default = policy;
+        for conflict in take(conflicts).into_iter() {
+            resolver.policies.insert(conflict, policy);
+        }
+    } else if let Some(conflict) = conflicts.pop() {
+        resolver.policies.insert(conflict, policy);
+    }
+
+    true
+}
+
+/// Create files or directories with NAMES in current directory,
+/// ask the user how to handle the ones already existing.
+pub fn create_files(app: &mut App, names: Vec<String>, is_dir: bool) -> AppResult<()> {
+    let path = app.current_path();
+    let mut conflicts: Vec<PathBuf> = names.iter()
+        .map(|name| path.join(name))
+        .filter(|file| file.symlink_metadata().is_ok())
+        .collect();
+
+    conflicts.sort();
+    conflicts.dedup();
+    conflicts.reverse();
+
+    resolve_create_conflicts(app, CreateState {
+        names,
+        is_dir,
+        total_conflicts: conflicts.len(),
+        conflicts,
+        resolver: ConflictResolver::new(ConflictPolicy::Rename),
+    })?;
+
+    Ok(())
+}
+
+fn resolve_create_conflicts(app: &mut App, state: CreateState) -> AppResult<bool> {
+    if let Some(conflict) = state.conflicts.last() {
+        SwitchCase::new(
+            app,
+            create_conflict_switch,
+            true,
+            create_conflict_msg(&state, conflict),
+            SwitchCaseData::Struct(Box::new(state))
+        );
+
+        return Ok(false)
+    }
+
+    crate::command::create_file(
+        app,
+        state.names.iter().map(String::as_str),
+        state.is_dir,
+        &state.resolver
+    )?;
+
+    Ok(true)
+}
+
+fn create_conflict_switch(
     app: &mut App,
+    key: char,
+    data: SwitchCaseData
+) -> AppResult<bool>
+{
+    let mut state = if let SwitchCaseData::Struct(data) = data {
+        match data.as_any().downcast_ref::<CreateState>() {
+            Some(state) => state.to_owned(),
+            None => panic!("Unknow panic occurred at create_conflict_switch in paste_operation.rs!"),
+        }
+    } else {
+        panic!("Unexpected situation at create_conflict_switch in paste_operation.rs.")
+    };
+
+    if key.eq_ignore_ascii_case(&'x') {
+        return Ok(true)
+    }
+
+    if !choose_policy(key, &mut state.resolver, &mut state.conflicts, false) {
+        return Ok(false)
+    }
+
+    resolve_create_conflicts(app, state)
+}
+
+/// Paste FILES to TARGET in background.
+fn paste_job(app: &mut App,
+             files: HashMap<PathBuf, MarkedFiles>,
+             target: PathBuf,
+             resolver: ConflictResolver,
+             moving: bool
+) -> AppResult<()>
+{
+    let kind = if moving { JobKind::Move } else { JobKind::Copy };
+
+    app.spawn_job(kind, target.to_owned(), move |context| {
+        let mut operations: Vec<FsOperation> = Vec::new();
+        let (failed_files, mut errors) = paste_files(
+            files.iter(),
+            &target,
+            &resolver,
+            moving,
+            &mut operations,
+            context
+        );
+
+        if moving {
+            let _errs = remove_origin_files(files.into_iter(), failed_files);
+            errors.append_errors(_errs.iter());
+        }
+
+        JobResult {
+            errors,
+            operations: moved_operations(operations),
+        }
+    })
+}
+
+/// The copied files whose origin has been removed are recorded as moved.
+fn moved_operations(operations: Vec<FsOperation>) -> Vec<FsOperation> {
+    operations.into_iter().map(|operation| match operation {
+        FsOperation::Copied(from, to) if from.symlink_metadata().is_err() => {
+            FsOperation::Renamed(from, to)
+        },
+        _ => operation
+    }).collect()
+}
+
+/// Remove the original files that have been copied to target path, return errors.
+fn remove_origin_files<I>(
     files: I,
     failed_files: HashMap<PathBuf, Vec<String>>
-) -> AppResult<()>
+) -> AppError
 where I: Iterator<Item = (PathBuf, MarkedFiles)>
 {
+    let mut errors = AppError::new();
+
     for (path, files) in files {
         // Avoid removing files that failed to be moved to target path.
-        let marked_ref = app.marked_files.get_mut(&path).unwrap();
-        let mut temp_files: HashMap<String, bool>;
+        let failed = failed_files.get(&path);
 
-        if let Some(failed) = failed_files.get(&path) {
-            temp_files = HashMap::new();
+        for (name, is_dir) in files.files.into_iter() {
+            if failed.is_some_and(|failed| failed.contains(&name)) {
+                continue;
+            }
 
-            for (name, is_dir) in files.files.into_iter() {
-                if !failed.contains(&name) {
-                    marked_ref.files.remove(&name);
-                    temp_files.entry(name).or_insert(is_dir);
-                }
+            let full_path = path.join(name);
+            // The file has been renamed to target path.
+            if full_path.symlink_metadata().is_err() {
+                continue;
             }
-        } else {
-            temp_files = files.files;
-            marked_ref.files.clear();
-        };
 
-        delete_file(
-            app,
-            path,
-            temp_files.into_iter(),
-            false,
-            false       // Not necesary
-        )?;
+            let remove_result = if is_dir {
+                fs::remove_dir_all(full_path)
+            } else {
+                fs::remove_file(full_path)
+            };
+
+            if let Err(err) = remove_result {
+                errors.add_error(err);
+            }
+        }
     }
 
-    Ok(())
+    errors
+}
+
+fn conflict_msg(state: &PasteState, conflict: &Path) -> CmdContent {
+    let name = conflict.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let existing = state.target.join(&name);
+
+    let incoming_dir = conflict.parent()
+        .and_then(|path| state.files.get(path))
+        .and_then(|files| files.files.get(&name))
+        .copied()
+        .unwrap_or(false);
+    let existing_dir = existing.symlink_metadata().is_ok_and(|metadata| metadata.is_dir());
+    let mismatch = if incoming_dir != existing_dir {
+        "\n  A directory and a file cannot overwrite each other, overwriting fails for it."
+    } else {
+        ""
+    };
+
+    CmdContent::String(format!(
+        "[o] overwrite  [s] skip  [r] rename  [n] keep newer  [x] cancel
+Press the uppercase key to apply it to all the rest conflicts.
+\nConflict ({}/{}): {}\n  Existing: {}\n  Incoming: {}{}",
+        state.total_conflicts - state.conflicts.len() + 1,
+        state.total_conflicts,
+        existing.to_string_lossy(),
+        file_info(&existing),
+        file_info(conflict),
+        mismatch
+    ))
+}
+
+fn create_conflict_msg(state: &CreateState, conflict: &Path) -> CmdContent {
+    let existing_dir = conflict.symlink_metadata().is_ok_and(|metadata| metadata.is_dir());
+    let mismatch = if existing_dir != state.is_dir {
+        "\n  A directory and a file cannot overwrite each other, overwriting fails for it."
+    } else {
+        ""
+    };
+
+    CmdContent::String(format!(
+        "[o] overwrite  [s] skip  [r] rename  [x] cancel
+Press the uppercase key to apply it to all the rest conflicts.
+\nConflict ({}/{}): {}\n  Existing: {}\n  Incoming: new {}{}",
+        state.total_conflicts - state.conflicts.len() + 1,
+        state.total_conflicts,
+        conflict.to_string_lossy(),
+        file_info(conflict),
+        if state.is_dir { "directory" } else { "file" },
+        mismatch
+    ))
+}
+
+/// Get the size & modified time of PATH.
+fn file_info(path: &Path) -> String {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) => return err.to_string()
+    };
+
+    let modified = metadata.modified()
+        .map(|time| {
+            DateTime::<Local>::from(time)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    if metadata.is_dir() {
+        format!("directory  {}", modified)
+    } else {
+        format!("{}  {}", file_size::fit_4(metadata.len()), modified)
+    }
 }
 
 fn generate_msg(app: &App) -> CmdContent {
@@ -347,3 +862,40 @@ fn restore_status(app: &mut App) -> AppResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, atomic::AtomicBool, mpsc};
+
+    use super::*;
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_overwrite_mismatch() {
+        let root = TestDir::new("paste");
+        fs::create_dir_all(root.join("from/a")).unwrap();
+        fs::create_dir_all(root.join("to")).unwrap();
+        fs::write(root.join("to/a"), "file").unwrap();
+
+        let mut marked = MarkedFiles::default();
+        marked.files.insert(String::from("a"), true);
+        let files = HashMap::from([(root.join("from"), marked)]);
+
+        let (sender, _receiver) = mpsc::channel();
+        let mut context = JobContext::new(0, sender, Arc::new(AtomicBool::new(false)));
+        let mut operations = Vec::new();
+        let (failed_files, errors) = paste_files(
+            files.iter(),
+            root.join("to"),
+            &ConflictResolver::new(ConflictPolicy::Overwrite),
+            false,
+            &mut operations,
+            &mut context
+        );
+
+        assert!(!errors.is_empty());
+        assert!(operations.is_empty());
+        assert_eq!(failed_files[&root.join("from")], vec![String::from("a")]);
+        assert_eq!(fs::read_to_string(root.join("to/a")).unwrap(), "file");
+    }
+}
@@ -0,0 +1,342 @@
+// Change Permissions & Owner
+
+use std::fs::{self, Permissions};
+use std::os::unix::fs::{chown as chown_file, lchown, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+
+use super::job::{JobContext, JobKind, JobResult};
+use super::paste_operation::item_size;
+
+use crate::app::App;
+use crate::error::{AppError, AppResult, ErrorType};
+use crate::utils::{group_id, user_id};
+use crate::rt_error;
+
+/// The change of permission bits.
+enum ModeChange {
+    /// Format: 755 or 4755
+    Octal(u32),
+
+    /// Format: u+x,go-w
+    Symbolic(Vec<Clause>),
+}
+
+/// A clause of symbolic mode like `go-w`, which may contain several operations like `u+r-x`.
+struct Clause {
+    /// Shifts of permission bits for user (6), group (3) & others (0).
+    classes: Vec<u32>,
+
+    /// Format: (operator, permission characters)
+    operations: Vec<(char, String)>,
+}
+
+impl ModeChange {
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        let invalid = || anyhow!("Invalid mode: {}", value);
+
+        if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+            let mode = u32::from_str_radix(value, 8).map_err(|_| invalid())?;
+            if value.len() > 4 {
+                return Err(invalid())
+            }
+
+            return Ok(Self::Octal(mode))
+        }
+
+        let mut clauses = Vec::new();
+        for clause in value.split(',') {
+            let who_end = clause.find(['+', '-', '=']).ok_or_else(invalid)?;
+            let (who, rest) = clause.split_at(who_end);
+
+            let mut classes = Vec::new();
+            for c in who.chars() {
+                match c {
+                    'u' => classes.push(6),
+                    'g' => classes.push(3),
+                    'o' => classes.push(0),
+                    'a' => classes.extend([6, 3, 0]),
+                    _ => return Err(invalid())
+                }
+            }
+            if classes.is_empty() {
+                classes.extend([6, 3, 0]);
+            }
+
+            let mut operations: Vec<(char, String)> = Vec::new();
+            for c in rest.chars() {
+                match c {
+                    '+' | '-' | '=' => operations.push((c, String::new())),
+                    'r' | 'w' | 'x' | 'X' | 's' | 't' => {
+                        // The first character of REST is always an operator.
+                        operations.last_mut().ok_or_else(invalid)?.1.push(c)
+                    },
+                    _ => return Err(invalid())
+                }
+            }
+
+            clauses.push(Clause { classes, operations });
+        }
+
+        Ok(Self::Symbolic(clauses))
+    }
+
+    /// Get the new permission bits from MODE of a file.
+    fn apply(&self, mode: u32, is_dir: bool) -> u32 {
+        let clauses = match self {
+            ModeChange::Octal(mode) => return *mode,
+            ModeChange::Symbolic(clauses) => clauses,
+        };
+
+        let mut mode = mode & 0o7777;
+        for clause in clauses.iter() {
+            for (operator, permissions) in clause.operations.iter() {
+                let bits = clause.bits(permissions, is_dir || mode & 0o111 != 0);
+                match operator {
+                    '+' => mode |= bits,
+                    '-' => mode &= !bits,
+                    _ => mode = (mode & !clause.bits("rwxst", true)) | bits,
+                }
+            }
+        }
+
+        mode
+    }
+}
+
+impl Clause {
+    /// Get the bits of PERMISSIONS for classes of the clause,
+    /// SEARCHABLE decides whether `X` means the execute bit.
+    fn bits(&self, permissions: &str, searchable: bool) -> u32 {
+        let mut bits = 0;
+
+        for c in permissions.chars() {
+            for shift in self.classes.iter() {
+                bits |= match (c, shift) {
+                    ('r', _) => 0o4 << shift,
+                    ('w', _) => 0o2 << shift,
+                    ('x', _) => 0o1 << shift,
+                    ('X', _) if searchable => 0o1 << shift,
+                    ('s', 6) => 0o4000,
+                    ('s', 3) => 0o2000,
+                    ('t', 0) => 0o1000,
+                    _ => 0
+                };
+            }
+        }
+
+        bits
+    }
+}
+
+/// Change permissions of marked files or the selected one, ARGS is like `[-R] <mode>`.
+pub fn chmod(app: &mut App, args: &[String]) -> AppResult<()> {
+    let (recursive, args) = recursive_flag(args);
+    let [mode] = args else {
+        rt_error!("Usage: chmod [-R] <mode>")
+    };
+
+    let change = ModeChange::from_str(mode)?;
+    apply_to_targets(app, JobKind::Chmod, recursive, move |path, metadata, top| {
+        // Permissions of symbolic links cannot be changed.
+        if !top && metadata.is_symlink() {
+            return Ok(())
+        }
+
+        let metadata = if top { fs::metadata(path)? } else { metadata.clone() };
+        let mode = change.apply(metadata.permissions().mode(), metadata.is_dir());
+        fs::set_permissions(path, Permissions::from_mode(mode))
+    })
+}
+
+/// Change owner of marked files or the selected one, ARGS is like `[-R] <user>[:group]`.
+pub fn chown(app: &mut App, args: &[String]) -> AppResult<()> {
+    let (recursive, args) = recursive_flag(args);
+    let [owner] = args else {
+        rt_error!("Usage: chown [-R] <user>[:group]")
+    };
+
+    let (user, group) = match owner.split_once(':') {
+        Some((user, group)) => (user, Some(group)),
+        None => (owner.as_str(), None)
+    };
+
+    let uid = match user {
+        "" => None,
+        user => match user_id(user) {
+            Some(uid) => Some(uid),
+            None => rt_error!(format!("Unknown user: {}", user))
+        }
+    };
+    let gid = match group {
+        None | Some("") => None,
+        Some(group) => match group_id(group) {
+            Some(gid) => Some(gid),
+            None => rt_error!(format!("Unknown group: {}", group))
+        }
+    };
+    if uid.is_none() && gid.is_none() {
+        rt_error!("Usage: chown [-R] <user>[:group]")
+    }
+
+    apply_to_targets(app, JobKind::Chown, recursive, move |path, metadata, top| {
+        // Symbolic links inside directories are changed themselves.
+        if !top && metadata.is_symlink() {
+            lchown(path, uid, gid)
+        } else {
+            chown_file(path, uid, gid)
+        }
+    })
+}
+
+/// Split the `-R` flag from ARGS.
+fn recursive_flag(args: &[String]) -> (bool, &[String]) {
+    match args.split_first() {
+        Some((flag, rest)) if flag == "-R" => (true, rest),
+        _ => (false, args)
+    }
+}
+
+/// Apply CHANGE to marked files or the selected one, and the files inside them when RECURSIVE.
+/// CHANGE receives the path, its metadata without following symlinks & whether it's a target.
+/// The recursive changes run as a background job, which could be cancelled.
+fn apply_to_targets<F>(app: &mut App, kind: JobKind, recursive: bool, change: F) -> AppResult<()>
+where F: Fn(&Path, &fs::Metadata, bool) -> std::io::Result<()> + Send + 'static
+{
+    let targets: Vec<PathBuf> = if app.marked_files.is_empty() {
+        match app.get_file_saver() {
+            Some(file) => vec![app.path.join(&file.name)],
+            None => return Err(ErrorType::NoSelected.pack())
+        }
+    } else {
+        app.marked_files.iter()
+            .flat_map(|(dir, marked)| marked.files.keys().map(|name| dir.join(name)))
+            .collect()
+    };
+
+    app.marked_files.clear();
+    app.mark_expand = false;
+
+    if recursive {
+        return app.spawn_job(kind, app.path.to_owned(), move |context| {
+            let total = targets.iter().map(|target| item_size(target).0).sum();
+            context.set_total(total, 0);
+
+            let mut errors = change_files(&targets, true, change, Some(context));
+            if context.cancelled() {
+                errors.add_error(anyhow!("{} is cancelled", kind.name()));
+            }
+
+            JobResult { errors, operations: Vec::new() }
+        })
+    }
+
+    let errors = change_files(&targets, false, change, None);
+    app.update_with_prev_selected(None)?;
+
+    if !errors.is_empty() {
+        return Err(errors)
+    }
+
+    Ok(())
+}
+
+/// Apply CHANGE to TARGETS, walking into directories when RECURSIVE.
+fn change_files<F>(targets: &[PathBuf],
+                   recursive: bool,
+                   change: F,
+                   mut context: Option<&mut JobContext>
+) -> AppError
+where F: Fn(&Path, &fs::Metadata, bool) -> std::io::Result<()>
+{
+    let mut errors = AppError::new();
+    for target in targets.iter() {
+        let mut paths = vec![(target.to_owned(), true)];
+
+        while let Some((path, top)) = paths.pop() {
+            if let Some(ref mut context) = context {
+                if context.cancelled() {
+                    return errors
+                }
+                context.add_progress(1, 0);
+            }
+
+            let result = path.symlink_metadata().and_then(|metadata| {
+                change(&path, &metadata, top)?;
+
+                // Directories linked by symlinks are not entered.
+                if recursive && metadata.is_dir() {
+                    for entry in fs::read_dir(&path)? {
+                        paths.push((entry?.path(), false));
+                    }
+                }
+
+                Ok(())
+            });
+
+            if let Err(err) = result {
+                errors.add_error(anyhow!("{}: {}", path.to_string_lossy(), err));
+            }
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Arc, atomic::AtomicBool};
+    use crate::utils::TestDir;
+
+    #[test]
+    fn test_mode_change() {
+        let apply = |change: &str, mode: u32, is_dir: bool| {
+            ModeChange::from_str(change).unwrap().apply(mode, is_dir)
+        };
+
+        assert_eq!(apply("750", 0o644, false), 0o750);
+        assert_eq!(apply("u+x", 0o644, false), 0o744);
+        assert_eq!(apply("go-w,+x", 0o666, false), 0o755);
+        assert_eq!(apply("a=r", 0o4755, false), 0o444);
+        assert_eq!(apply("o=rX", 0o750, false), 0o755);
+        assert_eq!(apply("o=rX", 0o640, false), 0o644);
+        assert_eq!(apply("u+s,g-x+s", 0o755, false), 0o6745);
+        assert_eq!(apply("+t", 0o777, true), 0o1777);
+        assert_eq!(apply("u+rw-x", 0o100, false), 0o600);
+
+        for invalid in ["", "8", "77777", "u", "z+x", "u+q", "u+x,"] {
+            assert!(ModeChange::from_str(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_change_files() {
+        let root = TestDir::new("permissions");
+        fs::create_dir_all(root.join("dir/sub")).unwrap();
+        fs::write(root.join("dir/sub/file"), "").unwrap();
+
+        let change = ModeChange::from_str("o-r").unwrap();
+        let chmod = move |path: &Path, metadata: &fs::Metadata, _| {
+            let mode = change.apply(metadata.permissions().mode(), metadata.is_dir());
+            fs::set_permissions(path, Permissions::from_mode(mode))
+        };
+        let other_readable = |path: &str| {
+            root.join(path).metadata().unwrap().permissions().mode() & 0o004 != 0
+        };
+        let targets = [root.join("dir")];
+
+        // Nothing is changed by a cancelled job.
+        let (sender, _receiver) = mpsc::channel();
+        let mut context = JobContext::new(0, sender.clone(), Arc::new(AtomicBool::new(true)));
+        assert!(change_files(&targets, true, &chmod, Some(&mut context)).is_empty());
+        assert!(other_readable("dir"));
+
+        let mut context = JobContext::new(1, sender, Arc::new(AtomicBool::new(false)));
+        assert!(change_files(&targets, true, &chmod, Some(&mut context)).is_empty());
+        for path in ["dir", "dir/sub", "dir/sub/file"] {
+            assert!(!other_readable(path), "{}", path);
+        }
+    }
+}
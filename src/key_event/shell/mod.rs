@@ -2,9 +2,11 @@
 
 mod utils;
 mod types;
+mod open_with;
 
 pub use types::*;
 pub use utils::*;
+pub use open_with::*;
 
 use crate::{
     config::{Config, ConfigValue},
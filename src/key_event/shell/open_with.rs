@@ -0,0 +1,196 @@
+// Open With
+
+use std::thread;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use ratatui::DefaultTerminal;
+use ratatui::text::{Line, Text};
+
+use super::{default_shell, run_in_terminal};
+
+use crate::key_event::{SwitchCase, SwitchCaseData};
+use crate::key_event::archive::extract_if_archived;
+use crate::{option_get, rt_error};
+use crate::app::App;
+use crate::utils::{CmdContent, FilePattern};
+use crate::config::{Config, ConfigValue, OpenRule};
+use crate::error::{AppResult, ErrorType, NotFoundType};
+
+/// Open FILE with the first matched rule.
+pub fn open_file_in_shell<P>(
+    app: &mut App,
+    terminal: &mut DefaultTerminal,
+    file: P
+) -> AppResult<()>
+where P: AsRef<Path>
+{
+    let rule = option_get!(
+        matched_rules(app, file.as_ref()).into_iter().next(),
+        format!("Cannot find program to open {}", file.as_ref().to_string_lossy())
+    );
+
+    run_open_rule(app, terminal, &rule, file.as_ref())
+}
+
+/// Show the programs that can open the selected file.
+pub fn open_with(app: &mut App) -> AppResult<()> {
+    let path = selected_file_path(app)?;
+    let rules = matched_rules(app, &path);
+
+    let mut msg = Text::raw("[0-9] open with the program\n");
+    msg.push_line("");
+
+    for (idx, rule) in rules.iter().take(10).enumerate() {
+        msg.push_line(Line::raw(format!(
+            "[{}] {}{}",
+            idx,
+            rule.name,
+            if rule.gui { "  (GUI)" } else { "" }
+        )));
+    }
+
+    SwitchCase::new(
+        app,
+        open_with_switch,
+        true,
+        CmdContent::Text(msg),
+        SwitchCaseData::None
+    );
+
+    Ok(())
+}
+
+fn open_with_switch(app: &mut App, key: char, _: SwitchCaseData) -> AppResult<bool> {
+    let idx = match key.to_digit(10) {
+        Some(idx) => idx as usize,
+        None => return Ok(true)
+    };
+
+    let path = selected_file_path(app)?;
+    let rule = option_get!(
+        matched_rules(app, &path).into_iter().nth(idx),
+        format!("Cannot find program with index {}", idx)
+    );
+
+    // The program is run after quitting the switch case, where the terminal is available.
+    app.pending_open = Some((rule, path));
+
+    Ok(true)
+}
+
+/// Run the program chosen in `open_with`.
+pub fn run_pending_open(app: &mut App, terminal: &mut DefaultTerminal) -> AppResult<()> {
+    if let Some((rule, path)) = app.pending_open.take() {
+        run_open_rule(app, terminal, &rule, &path)?;
+    }
+
+    Ok(())
+}
+
+fn selected_file_path(app: &App) -> AppResult<PathBuf> {
+    let file = if let Some(file) = app.get_file_saver() {
+        file
+    } else {
+        return Err(ErrorType::NotFound(
+            NotFoundType::Item(String::from("selected file"))
+        ).pack())
+    };
+
+    if file.is_dir {
+        rt_error!("Cannot open a directory with programs")
+    }
+
+    Ok(app.current_path().join(&file.name))
+}
+
+/// Get the rules matching PATH, the built-in rules are at the end.
+fn matched_rules(app: &App, path: &Path) -> Vec<OpenRule> {
+    let mut rules = if let ConfigValue::OpenRules(
+        rules
+    ) = Config::get_value(&app.config, "open_rules")
+    {
+        rules.to_owned()
+    } else {
+        Vec::new()
+    };
+
+    rules.push(OpenRule {
+        name: String::from("feh"),
+        command: String::from("feh $."),
+        gui: true,
+        refresh: false,
+        patterns: ["jpg", "jpeg", "png"].into_iter()
+            .map(FilePattern::extension)
+            .collect(),
+    });
+
+    if let ConfigValue::String(program) = Config::get_value(&app.config, "file_read_program") {
+        rules.push(OpenRule {
+            name: program.to_string(),
+            command: format!("{} $.", program),
+            gui: false,
+            refresh: true,
+            patterns: Vec::new(),
+        });
+    }
+
+    rules.retain(|rule| rule.is_match(path));
+    rules
+}
+
+fn run_open_rule(
+    app: &mut App,
+    terminal: &mut DefaultTerminal,
+    rule: &OpenRule,
+    path: &Path
+) -> AppResult<()>
+{
+    // Files inside archives are opened after extracted.
+    let path = extract_if_archived(path)?;
+    let quoted_path = shell_quote(&path.to_string_lossy());
+    let command = if rule.command.contains("$.") {
+        rule.command.replace("$.", &quoted_path)
+    } else {
+        format!("{} {}", rule.command, quoted_path)
+    };
+
+    let mut process = Command::new(default_shell(app)?);
+    process.arg("-c").arg(command);
+
+    if !rule.gui {
+        return run_in_terminal(app, terminal, process, false, rule.refresh)
+    }
+
+    let mut child = process
+        .current_dir(&app.path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // Reap the process after it exits.
+    thread::spawn(move || child.wait());
+
+    if rule.refresh {
+        app.update_with_prev_selected(None)?;
+    }
+
+    Ok(())
+}
+
+/// Quote STRING with single quotes to be used as a shell argument.
+fn shell_quote(string: &str) -> String {
+    format!("'{}'", string.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("a b.txt"), "'a b.txt'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
@@ -77,10 +77,4 @@ impl<'a> CommandStr<'a> {
         Ok(joined_str)
     }
 
-    /// Convert a CommandStr Vec into a &str Vec.
-    pub fn str_vec(str_vec: Vec<Self>) -> Vec<&'a str> {
-        str_vec.into_iter()
-            .map(|e| e.into())
-            .collect::<Vec<_>>()
-    }
 }
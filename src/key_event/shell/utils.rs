@@ -2,7 +2,7 @@
 
 use std::io::{self, stdout};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::Command;
 
 use ratatui::DefaultTerminal;
 use ratatui::crossterm::{
@@ -29,21 +29,7 @@ pub fn shell_process(
     refresh: bool
 ) -> AppResult<()>
 {
-    let shell_program = if let ConfigValue::String(
-        _shell
-    ) = Config::get_value(&app.config, "default_shell")
-    {
-        _shell.as_ref().to_owned()
-    } else {
-        std::env::var("SHELL")?
-    };
-
-    // For restore the original state
-    let current_file = if let Some(file) = app.get_file_saver() {
-        Some(file.name.to_owned())
-    } else {
-        None
-    };
+    let shell_program = default_shell(app)?;
 
     let mut wait_for_press = false;
     let mut process = Command::new(&shell_program);
@@ -79,6 +65,24 @@ pub fn shell_process(
         }
     }
 
+    run_in_terminal(app, terminal, process, wait_for_press, refresh)
+}
+
+/// Leave the alternate screen to run PROCESS, then come back after it exits.
+pub fn run_in_terminal(
+    app: &mut App,
+    terminal: &mut DefaultTerminal,
+    mut process: Command,
+    wait_for_press: bool,
+    refresh: bool
+) -> AppResult<()>
+{
+    // For restore the original state
+    let current_file = if let Some(file) = app.get_file_saver() {
+        Some(file.name.to_owned())
+    } else {
+        None
+    };
 
     // Preparation for running process
     process.current_dir(&app.path);
@@ -118,81 +122,16 @@ pub fn shell_process(
     Ok(())
 }
 
-/// Run `command` & get its output.
-pub fn fetch_output<P: AsRef<Path>>(
-    terminal: &mut DefaultTerminal,
-    current_path: P,
-    command: ShellCommand
-) -> anyhow::Result<String>
-{
-    if let ShellCommand::Command(_, mut args) = command {
-        let program: &str = args.remove(0).into();
-        let mut _command = Command::new(program);
-
-        _command.current_dir(current_path).stdout(Stdio::piped());
-        _command.args(CommandStr::str_vec(args));
-
-
-        disable_raw_mode()?;
-        execute!(stdout(), LeaveAlternateScreen, Show)?;
-
-        let output = _command.spawn()?.wait_with_output()?;
-
-        enable_raw_mode()?;
-        execute!(stdout(), EnterAlternateScreen, Hide)?;
-        terminal.clear()?;
-
-        return Ok(String::from_utf8(output.stdout)?)
+/// Get the shell program from `default_shell` config.
+pub fn default_shell(app: &App) -> AppResult<String> {
+    if let ConfigValue::String(
+        _shell
+    ) = Config::get_value(&app.config, "default_shell")
+    {
+        Ok(_shell.as_ref().to_owned())
+    } else {
+        Ok(std::env::var("SHELL")?)
     }
-
-    rt_error!("Cannot get the program that needs a output")
-}
-
-pub fn open_file_in_shell<P>(
-    app: &mut App,
-    terminal: &mut DefaultTerminal,
-    file: P
-) -> AppResult<()>
-where P: AsRef<Path>
-{
-    let file_path = file.as_ref();
-    let file_type = file_path
-        .extension()
-        .and_then(std::ffi::OsStr::to_str)
-        .unwrap_or_default();
-
-    let mut refresh = false;
-    let shell_command = match file_type {
-        "jpg" | "jpge" | "png" => String::from("feh"),
-        _ => {
-            refresh = true;
-
-            if let ConfigValue::String(_str) = Config::get_value(
-                &app.config,
-                "file_read_program"
-            )
-            {
-                _str.as_ref().to_owned()
-            } else {
-                panic!("Unknown error occurred at open_file_in_shell fn in utils.rs!")
-            }
-        }
-    };
-
-    shell_process(
-        app,
-        terminal,
-        ShellCommand::Command(
-            None,
-            vec![
-                CommandStr::Str(&shell_command),
-                CommandStr::Str(file_path.to_str().unwrap())
-            ]
-        ),
-        refresh
-    )?;
-
-    Ok(())
 }
 
 pub fn fetch_working_directory() -> AppResult<PathBuf> {
@@ -0,0 +1,175 @@
+// Sort Operation.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use toml_edit::{value, DocumentMut, InlineTable};
+use ratatui::{style::Stylize, text::{Line, Text}};
+
+use super::{SwitchCase, SwitchCaseData};
+
+use crate::app::{App, SortKey, SortMode};
+use crate::utils::CmdContent;
+use crate::config::{get_document, write_document};
+use crate::error::AppResult;
+
+/// The sort modes of file lists.
+#[derive(Default)]
+pub struct SortConfig {
+    /// The mode used by directories without specific one.
+    pub global: SortMode,
+    directories: HashMap<PathBuf, SortMode>,
+}
+
+impl SortConfig {
+    pub fn get(&self, path: &Path) -> SortMode {
+        *self.directories.get(path).unwrap_or(&self.global)
+    }
+}
+
+/// Open the sort menu, or sort with KEY of the menu directly.
+pub fn sort_operation(app: &mut App, key: Option<char>) -> AppResult<()> {
+    if let Some(key) = key {
+        sort_switch(app, key, SwitchCaseData::None)?;
+        return Ok(())
+    }
+
+    SwitchCase::new(
+        app,
+        sort_switch,
+        true,
+        generate_msg(app),
+        SwitchCaseData::None
+    );
+
+    Ok(())
+}
+
+fn sort_switch(app: &mut App, key: char, _: SwitchCaseData) -> AppResult<bool> {
+    // Uppercase keys modify the global sort mode.
+    let global = key.is_ascii_uppercase();
+    let path = app.current_path();
+    let mut mode = if global {
+        app.sort_config.global
+    } else {
+        app.sort_config.get(&path)
+    };
+
+    match key.to_ascii_lowercase() {
+        'n' => mode.key = SortKey::Name,
+        'i' => mode.key = SortKey::IgnoreCase,
+        'v' => mode.key = SortKey::Natural,
+        's' => mode.key = SortKey::Size,
+        'm' => mode.key = SortKey::Modified,
+        'e' => mode.key = SortKey::Extension,
+        'r' => mode.reverse = !mode.reverse,
+        'c' if !global => {
+            remove_directory_mode(app, &path)?;
+            app.update_with_prev_selected(None)?;
+            return Ok(true)
+        },
+        _ => return Ok(true)
+    }
+
+    if global {
+        set_global_mode(app, mode)?;
+    } else {
+        set_directory_mode(app, path, mode)?;
+    }
+    app.update_with_prev_selected(None)?;
+
+    Ok(true)
+}
+
+fn set_global_mode(app: &mut App, mode: SortMode) -> AppResult<()> {
+    let mut toml_config = get_document(app.config_path.to_owned())?;
+    toml_config["sort_mode"] = value(mode.to_string());
+
+    write_document(toml_config)?;
+    app.sort_config.global = mode;
+
+    Ok(())
+}
+
+fn set_directory_mode(app: &mut App, path: PathBuf, mode: SortMode) -> AppResult<()> {
+    let mut toml_config = get_document(app.config_path.to_owned())?;
+    if toml_config.get("directory_sort_mode").is_none() {
+        toml_config["directory_sort_mode"] = value(InlineTable::new());
+    }
+    toml_config["directory_sort_mode"][path.to_string_lossy().as_ref()] = value(
+        mode.to_string()
+    );
+
+    write_document(toml_config)?;
+    app.sort_config.directories.insert(path, mode);
+
+    Ok(())
+}
+
+fn remove_directory_mode(app: &mut App, path: &Path) -> AppResult<()> {
+    let mut toml_config = get_document(app.config_path.to_owned())?;
+    if let Some(table) = toml_config
+        .get_mut("directory_sort_mode")
+        .and_then(|item| item.as_table_like_mut())
+    {
+        table.remove(path.to_string_lossy().as_ref());
+    }
+
+    write_document(toml_config)?;
+    app.sort_config.directories.remove(path);
+
+    Ok(())
+}
+
+fn generate_msg(app: &App) -> CmdContent {
+    let path = app.current_path();
+    let mut msg = Text::raw(
+        "[n] name  [i] ignore case  [v] natural  [s] size  [m] modified time  [e] extension"
+    );
+    msg.push_line("[r] reverse  [c] use global mode in current directory");
+    msg.push_line("Lowercase keys change current directory, uppercase keys change the global mode.");
+    msg.push_line("");
+
+    let scope = if app.sort_config.directories.contains_key(&path) {
+        "current directory"
+    } else {
+        "global"
+    };
+    msg.push_line(Line::raw(format!(
+        "Current: {} ({})",
+        app.sort_config.get(&path),
+        scope
+    )).bold());
+
+    CmdContent::Text(msg)
+}
+
+/// Read config for sort modes.
+pub fn read_config(app: &mut App, document: &DocumentMut) -> anyhow::Result<()> {
+    if let Some(item) = document.get("sort_mode") {
+        if let Some(mode) = item.as_str() {
+            app.sort_config.global = SortMode::from_str(mode)?;
+        } else {
+            bail!("Wrong type for sort_mode config")
+        }
+    }
+
+    if let Some(item) = document.get("directory_sort_mode") {
+        if let Some(table) = item.as_table_like() {
+            for (path, mode) in table.iter() {
+                let mode = if let Some(mode) = mode.as_str() {
+                    SortMode::from_str(mode)?
+                } else {
+                    bail!("Wrong type for directory_sort_mode config")
+                };
+
+                app.sort_config.directories.insert(PathBuf::from(path), mode);
+            }
+        } else {
+            bail!("Wrong type for directory_sort_mode config")
+        }
+    }
+
+    Ok(())
+}
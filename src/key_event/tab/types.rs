@@ -25,8 +25,8 @@ pub struct TabList<'a> {
     /// The selected file index in every tab.
     pub(super) selected_file: Vec<Option<usize>>,
 
-    /// Store current path & whether hiding files.
-    pub(super) list: Vec<(PathBuf, bool)>,
+    /// Store current path, whether hiding files & whether hiding ignored files.
+    pub(super) list: Vec<(PathBuf, bool, bool)>,
 
     /// A collection of specific tabs stored in auto_config.toml
     pub(super) storage: Vec<Rc<[Cow<'a, str>]>>,
@@ -86,7 +86,7 @@ impl TabState {
 impl<'a> TabList<'a> {
     pub fn new(path: PathBuf) -> Self {
         TabList {
-            list: vec![(path, false)],
+            list: vec![(path, false, false)],
             selected_file: vec![None],
             storage: Vec::new(),
             current: 0,
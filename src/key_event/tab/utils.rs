@@ -41,6 +41,50 @@ pub fn tab_operation(app: &mut App) -> AppResult<()> {
     Ok(())
 }
 
+/// Run tab operation from command line, like `:tab new /tmp`.
+pub fn tab_command(app: &mut App, args: &[String]) -> AppResult<()> {
+    let operation = option_get!(args.first(), "Missing the operation of tab");
+    update_current_tab(app);
+
+    match operation.as_str() {
+        "new" => {
+            let path = match args.get(1) {
+                Some(path) => app.current_path().join(path).canonicalize()?,
+                None => app.path.to_owned()
+            };
+            if !path.is_dir() {
+                rt_error!(format!("{} is not a directory", path.to_string_lossy()))
+            }
+
+            create(app);
+            app.goto_dir(path, None)?;
+            update_current_tab(app);
+        },
+
+        "next"  => {next(app)?;},
+        "prev"  => {prev(app)?;},
+        "only"  => delete_other_tabs(app),
+        "close" => {remove_base(app, app.tab_list.current)?;},
+
+        "switch" => {
+            let idx = option_get!(
+                args.get(1).and_then(|idx| idx.parse::<usize>().ok()),
+                "Missing the index of tab"
+            );
+            if idx == 0 || idx > app.tab_list.list.len() {
+                rt_error!(format!("There's no tab {}", idx))
+            }
+
+            app.tab_list.current = idx - 1;
+            select_new(app)?;
+        },
+
+        _ => rt_error!(format!("Unknow tab operation: {}", operation))
+    }
+
+    Ok(())
+}
+
 // Core util functions
 fn switch(app: &mut App, key: char, _data: SwitchCaseData) -> AppResult<bool> {
     let mut data = if let SwitchCaseData::Struct(data) = _data {
@@ -148,7 +192,7 @@ pub fn prev(app: &mut App) -> AppResult<bool> {
 #[inline]
 fn create(app: &mut App) {
     let tab = &mut app.tab_list;
-    tab.list.push((app.path.to_owned(), app.hide_files));
+    tab.list.push((app.path.to_owned(), app.hide_files, app.ignore_files));
     tab.selected_file.push(None);
     tab.current = tab.list.len() - 1;
 }
@@ -254,6 +298,7 @@ fn handle_tabs(app: &mut App, key: char, data: &mut TabState) -> AppResult<bool>
     let tab = &mut app.tab_list;
     if let Some(path) = tab.list.get(idx - 1).cloned() {
         tab.current = idx - 1;
+        app.ignore_files = path.2;
         app.goto_dir(path.0, Some(path.1))?;
 
         if let Some(idx) = app.tab_list.selected_file[app.tab_list.current] {
@@ -273,11 +318,11 @@ fn apply_storage_tabs(app: &mut App, idx: usize) -> AppResult<bool> {
         return Err(ErrorType::NotFound(NotFoundType::None).pack())
     }
 
-    let mut tabs: Vec<(PathBuf, bool)> = Vec::new();
+    let mut tabs: Vec<(PathBuf, bool, bool)> = Vec::new();
     for path_str in app.tab_list.storage[idx].iter() {
         let path = PathBuf::from(path_str.as_ref());
         let is_hidden = path_is_hidden(&path);
-        tabs.push((path, is_hidden));
+        tabs.push((path, is_hidden, app.ignore_files));
     }
 
     if tabs.is_empty() {
@@ -317,7 +362,7 @@ fn save_tabs(app: &mut App) -> anyhow::Result<()> {
     };
 
     let mut fmt_tabs: Vec<Cow<str>> = Vec::new();
-    for (path, _) in tabs.into_iter() {
+    for (path, _, _) in tabs.into_iter() {
         let tab_path = if let Ok(_path) = path.into_os_string().into_string() {
             _path
         } else {
@@ -371,7 +416,8 @@ fn update_current_tab(app: &mut App) {
     let tab = &mut app.tab_list;
     tab.list[tab.current] = (
         app.path.to_owned(),
-        app.hide_files
+        app.hide_files,
+        app.ignore_files
     );
     tab.selected_file[tab.current] = selected_idx;
 }
@@ -381,6 +427,7 @@ fn select_new(app: &mut App) -> AppResult<()> {
         .get(app.tab_list.current)
         .expect("Failed when switching tab!")
         .to_owned();
+    app.ignore_files = target_tab.2;
     app.goto_dir(target_tab.0, Some(target_tab.1))?;
 
     if let Some(idx) = app.tab_list.selected_file[app.tab_list.current] {
@@ -442,7 +489,7 @@ where I: Iterator<Item = &'a Rc<[Cow<'a, str>]>>
 
 #[inline]
 fn tab_string_list<'a, I>(text: &mut Text, iter: I, current: usize)
-where I: Iterator<Item = &'a (PathBuf, bool)>
+where I: Iterator<Item = &'a (PathBuf, bool, bool)>
 {
     let mut idx = 1;
 
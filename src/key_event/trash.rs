@@ -0,0 +1,387 @@
+// Trash (FreeDesktop.org Trash specification)
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime};
+use ratatui::{style::Stylize, text::{Line, Text}};
+
+use crate::app::App;
+use crate::utils::CmdContent;
+use crate::error::{AppError, AppResult, ErrorType, NotFoundType};
+
+use super::{SwitchCase, SwitchCaseData};
+
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// An item stored in the trash can.
+#[derive(Clone)]
+pub struct TrashEntry {
+    /// The name of the item inside `Trash/files`.
+    pub trash_name: String,
+    pub original_path: PathBuf,
+    pub deletion_date: Option<NaiveDateTime>,
+}
+
+impl TrashEntry {
+    pub fn trashed_path(&self) -> io::Result<PathBuf> {
+        Ok(trash_dir()?.join("files").join(&self.trash_name))
+    }
+
+    fn info_path(&self) -> io::Result<PathBuf> {
+        Ok(trash_dir()?.join("info").join(format!("{}.trashinfo", self.trash_name)))
+    }
+}
+
+/// Get the home trash directory and create it if it doesn't exist.
+pub fn trash_dir() -> io::Result<PathBuf> {
+    let data_home = match env::var("XDG_DATA_HOME") {
+        Ok(path) if !path.is_empty() => PathBuf::from(path),
+        _ => {
+            let home = env::var("HOME").map_err(|_| io::Error::new(
+                ErrorKind::NotFound,
+                "Cannot get the home directory for trash"
+            ))?;
+            PathBuf::from(home).join(".local/share")
+        }
+    };
+
+    let trash = data_home.join("Trash");
+    fs::create_dir_all(trash.join("files"))?;
+    fs::create_dir_all(trash.join("info"))?;
+
+    Ok(trash)
+}
+
+/// Move PATH into the trash can, return the entry of it.
+/// PATH is required to be an absolute path.
+pub fn move_to_trash<P: AsRef<Path>>(path: P) -> io::Result<TrashEntry> {
+    let path = path.as_ref();
+    let trash = trash_dir()?;
+    let file_name = path
+        .file_name()
+        .ok_or(io::Error::new(ErrorKind::InvalidInput, "Cannot trash this path"))?
+        .to_string_lossy()
+        .to_string();
+
+    // Reserve the name by creating its info file atomically.
+    let deletion_date = Local::now().naive_local();
+    let mut idx = 1;
+    let (trash_name, mut info_file) = loop {
+        let name = if idx == 1 {
+            file_name.to_owned()
+        } else {
+            format!("{}.{}", file_name, idx)
+        };
+
+        let info_file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(trash.join("info").join(format!("{}.trashinfo", name)));
+
+        match info_file {
+            Ok(file) if !trash.join("files").join(&name).exists() => break (name, file),
+            Ok(_) => {
+                fs::remove_file(trash.join("info").join(format!("{}.trashinfo", name)))?;
+            },
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => (),
+            Err(err) => return Err(err),
+        }
+
+        idx += 1;
+    };
+
+    let info_path = trash.join("info").join(format!("{}.trashinfo", trash_name));
+    let write_result = info_file.write_all(format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        url_encode(&path.to_string_lossy()),
+        deletion_date.format(DATE_FORMAT)
+    ).as_bytes());
+
+    if let Err(err) = write_result.and_then(|_| {
+        move_item(path, &trash.join("files").join(&trash_name))
+    })
+    {
+        let _ = fs::remove_file(info_path);
+        return Err(err)
+    }
+
+    Ok(TrashEntry {
+        trash_name,
+        original_path: path.to_path_buf(),
+        deletion_date: Some(deletion_date),
+    })
+}
+
+/// Get all the entries of trash can, the latest deleted one comes first.
+pub fn trash_entries() -> io::Result<Vec<TrashEntry>> {
+    let info_dir = trash_dir()?.join("info");
+    let mut entries: Vec<TrashEntry> = Vec::new();
+
+    for entry in fs::read_dir(info_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let trash_name = if let Some(_name) = name.strip_suffix(".trashinfo") {
+            _name.to_owned()
+        } else {
+            continue;
+        };
+
+        let mut content = String::new();
+        fs::File::open(entry.path())?.read_to_string(&mut content)?;
+
+        if let Some(entry) = parse_trash_info(trash_name, &content) {
+            entries.push(entry);
+        }
+    }
+
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.deletion_date));
+
+    Ok(entries)
+}
+
+/// Restore ENTRY back to its original path.
+pub fn restore(entry: &TrashEntry) -> io::Result<()> {
+    if fs::symlink_metadata(&entry.original_path).is_ok() {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", entry.original_path.to_string_lossy())
+        ))
+    }
+
+    if let Some(parent) = entry.original_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    move_item(&entry.trashed_path()?, &entry.original_path)?;
+    fs::remove_file(entry.info_path()?)?;
+
+    Ok(())
+}
+
+/// Remove ENTRY from trash can permanently.
+pub fn remove_entry(entry: &TrashEntry) -> io::Result<()> {
+    let trashed = entry.trashed_path()?;
+
+    match fs::symlink_metadata(&trashed) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(trashed)?,
+        Ok(_) => fs::remove_file(trashed)?,
+        Err(err) if err.kind() == ErrorKind::NotFound => (),
+        Err(err) => return Err(err),
+    }
+
+    fs::remove_file(entry.info_path()?)
+}
+
+/// Rename FROM to TO, copy it instead when they're on different devices.
+pub(super) fn move_item(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::CrossesDevices => {
+            let metadata = fs::symlink_metadata(from)?;
+            if metadata.is_dir() {
+                copy_dir::copy_dir(from, to)?;
+                fs::remove_dir_all(from)
+            } else {
+                fs::copy(from, to)?;
+                fs::remove_file(from)
+            }
+        },
+        Err(err) => Err(err),
+    }
+}
+
+fn parse_trash_info(trash_name: String, content: &str) -> Option<TrashEntry> {
+    let mut original_path: Option<PathBuf> = None;
+    let mut deletion_date: Option<NaiveDateTime> = None;
+
+    for line in content.lines() {
+        if let Some(path) = line.strip_prefix("Path=") {
+            original_path = Some(PathBuf::from(url_decode(path)));
+        } else if let Some(date) = line.strip_prefix("DeletionDate=") {
+            deletion_date = NaiveDateTime::parse_from_str(date, DATE_FORMAT).ok();
+        }
+    }
+
+    Some(TrashEntry {
+        trash_name,
+        original_path: original_path?,
+        deletion_date
+    })
+}
+
+fn url_encode(path: &str) -> String {
+    let mut encoded = String::new();
+
+    for byte in path.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' |
+            b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+fn url_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded: Vec<u8> = Vec::new();
+
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' {
+            let hex = path.get(idx + 1..idx + 3).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                idx += 3;
+                continue;
+            }
+        }
+
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+
+// Commands
+/// Show items in trash can with their index.
+pub fn list_trash(app: &mut App) -> AppResult<()> {
+    let entries = trash_entries()?;
+    let mut text = Text::raw("[Trash]  :trash_restore <index...>  :trash_empty\n");
+    text.push_line("");
+
+    if entries.is_empty() {
+        text.push_line("The trash can is empty.");
+    }
+
+    for (idx, entry) in entries.iter().enumerate() {
+        let mut line = Line::raw(format!("[{}] ", idx + 1));
+        if let Some(date) = entry.deletion_date {
+            line.push_span(date.format("%Y-%m-%d %H:%M  ").to_string().dim());
+        }
+        line.push_span(entry.original_path.to_string_lossy().to_string());
+        text.push_line(line);
+    }
+
+    SwitchCase::new(
+        app,
+        |_, _, _| Ok(true),
+        true,
+        CmdContent::Text(text),
+        SwitchCaseData::None
+    );
+
+    Ok(())
+}
+
+/// Restore items with their index from `:trash_list`.
+/// Restore the latest deleted one when INDEXES is empty.
+pub fn restore_trash<'a, I>(app: &mut App, indexes: I) -> AppResult<()>
+where I: Iterator<Item = &'a str>
+{
+    let entries = trash_entries()?;
+    let mut errors = AppError::new();
+    let mut targets: Vec<&TrashEntry> = Vec::new();
+
+    for idx in indexes {
+        let entry = idx.parse::<usize>()
+            .ok()
+            .and_then(|_idx| entries.get(_idx.wrapping_sub(1)));
+
+        if let Some(entry) = entry {
+            targets.push(entry);
+        } else {
+            errors.add_error(ErrorType::NotFound(
+                NotFoundType::Item(format!("trash item {}", idx))
+            ));
+        }
+    }
+
+    if targets.is_empty() && errors.is_empty() {
+        if let Some(entry) = entries.first() {
+            targets.push(entry);
+        } else {
+            return Err(ErrorType::NotFound(NotFoundType::None).pack())
+        }
+    }
+
+    for entry in targets.into_iter() {
+        if let Err(err) = restore(entry) {
+            errors.add_error(err);
+        }
+    }
+
+    app.update_with_prev_selected(None)?;
+
+    if !errors.is_empty() {
+        return Err(errors)
+    }
+
+    Ok(())
+}
+
+/// Ask the user whether to empty trash can.
+pub fn empty_trash(app: &mut App) {
+    SwitchCase::new(
+        app,
+        empty_switch,
+        false,
+        CmdContent::Text(Text::raw(
+            "Are you sure to empty the trash can permanently? (y to confirm)"
+        ).red()),
+        SwitchCaseData::None
+    );
+}
+
+fn empty_switch(_: &mut App, key: char, _: SwitchCaseData) -> AppResult<bool> {
+    if key != 'y' {
+        return Ok(true)
+    }
+
+    let mut errors = AppError::new();
+    for entry in trash_entries()?.iter() {
+        if let Err(err) = remove_entry(entry) {
+            errors.add_error(err);
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors)
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_encoding() {
+        let path = "/home/user/some file%.txt";
+        let encoded = url_encode(path);
+
+        assert_eq!(encoded, "/home/user/some%20file%25.txt");
+        assert_eq!(url_decode(&encoded), path);
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let entry = parse_trash_info(
+            String::from("foo.2"),
+            "[Trash Info]\nPath=/tmp/a%20b/foo\nDeletionDate=2024-05-01T10:20:30\n"
+        ).unwrap();
+
+        assert_eq!(entry.original_path, PathBuf::from("/tmp/a b/foo"));
+        assert_eq!(
+            entry.deletion_date.unwrap().format(DATE_FORMAT).to_string(),
+            "2024-05-01T10:20:30"
+        );
+    }
+}
@@ -21,6 +21,7 @@ use key_event::{
     handle_event,
     shell_process,
     fetch_working_directory,
+    pending_keys_timeout,
 };
 
 fn main() -> AppResult<()> {
@@ -30,6 +31,11 @@ fn main() -> AppResult<()> {
     let mut app = App::default();
     let image_recvs = app.init_image_picker();
     let search_recv = app.init_search_channel();
+    let job_recv = app.init_job_channel();
+    let watch_recv = app.init_dir_watcher();
+    let preview_recv = app.init_previewer();
+    let git_recv = app.init_git_status();
+    let size_recv = app.init_dir_size();
 
     // Init config information.
     config::init_config(&mut app)?;
@@ -65,7 +71,8 @@ fn main() -> AppResult<()> {
             if let event::Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     if key.code == KeyCode::Char('q') &&
-                        key.modifiers.is_empty()
+                        key.modifiers.is_empty() &&
+                        app.pending_keys.is_empty()
                     {
                         match check_quit_condition(&mut app) {
                             QuitCheckRes::Quit => break,
@@ -78,19 +85,68 @@ fn main() -> AppResult<()> {
                     if let Err(err) = result {
                         app.app_error.append_errors(err.iter());
                     }
+
+                    // Operations made by a single key press can be undone together.
+                    app.journal.commit();
                 }
             }
         }
 
+        // Pending keys handler
+        if let Err(err) = pending_keys_timeout(&mut app, &mut terminal) {
+            app.app_error.append_errors(err.iter());
+        }
+
         // Search handler
-        if let Ok(idx_set) = search_recv.try_recv() {
-            app.file_searcher.update_idx(idx_set);
+        if let Ok(result) = search_recv.try_recv() {
+            app.file_searcher.update(result);
 
-            if let Err(err) = app.next_candidate() {
+            if let Err(err) = app.first_candidate() {
                 app.app_error.append_errors(err.iter());
             }
         }
 
+        // Finder handler
+        if let Some(ref mut finder) = app.finder {
+            finder.update();
+        }
+
+        // Grep handler
+        if let Some(ref mut grep) = app.grep {
+            grep.update();
+        }
+
+        // Job handler
+        while let Ok(message) = job_recv.try_recv() {
+            if let Err(err) = app.handle_job_message(message) {
+                app.app_error.append_errors(err.iter());
+            }
+        }
+
+        // Directory watcher handler
+        if let Some(ref watch_recv) = watch_recv {
+            if let Err(err) = app.handle_dir_changes(watch_recv.try_iter()) {
+                app.app_error.append_errors(err.iter());
+            }
+
+            app.update_watched_dirs();
+        }
+
+        // Previewer handler
+        while let Ok((path, mtime, output)) = preview_recv.try_recv() {
+            app.handle_preview_output(path, mtime, output);
+        }
+
+        // Git status handler
+        app.request_git_status(false);
+        while let Ok((path, repo)) = git_recv.try_recv() {
+            app.handle_git_status(path, repo);
+        }
+
+        // Directory size handler
+        app.request_disk_usage();
+        app.handle_dir_size(size_recv.try_iter());
+
         // Image perview handler
         if let Some((ref prx, ref irx)) = image_recvs {
             if app.image_preview.useless {
@@ -121,6 +177,8 @@ fn main() -> AppResult<()> {
     }
     
     ratatui::restore();
+    app.command_history.save()?;
+
     Ok(())
 }
 
@@ -190,6 +248,10 @@ fn check_quit_condition(app: &mut App) -> QuitCheckRes {
         return QuitCheckRes::Continue
     }
 
+    if app.finder.is_some() || app.grep.as_ref().is_some_and(|grep| grep.shown()) {
+        return QuitCheckRes::Continue
+    }
+
     use ratatui::style::Stylize;
     use crate::key_event::{SwitchCase, SwitchCaseData};
 
@@ -208,6 +270,21 @@ fn check_quit_condition(app: &mut App) -> QuitCheckRes {
         return QuitCheckRes::Reset
     }
 
+    if !app.jobs.is_empty() {
+        SwitchCase::new(
+            app,
+            really_quit,
+            false,
+            crate::utils::CmdContent::Text(
+                Text::raw(
+                    "There're background jobs running, are you sure to quit? (y for yes)",
+                ).red()
+            ),
+            SwitchCaseData::None
+        );
+        return QuitCheckRes::Reset
+    }
+
     QuitCheckRes::Quit
 }
 
@@ -38,10 +38,17 @@ pub fn render_child(app: &mut App, frame: &mut Frame, area: Rect) {
         }
     };
 
+    let child_path = app.get_file_saver()
+        .map(|file| app.current_path().join(&file.name));
+
     let (child_items, marked) = render_list(
         app.child_files.iter(),
         &app.term_colors,
-        marked_files
+        marked_files,
+        None,
+        app.git_status.repo().zip(child_path.as_deref()),
+        None,
+        None
     );
 
     frame.render_stateful_widget(
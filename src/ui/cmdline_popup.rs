@@ -4,20 +4,38 @@ use std::borrow::Cow;
 
 use ratatui::{
     widgets::{Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget},
-    style::{Style, Stylize},
+    style::{Color, Style, Styled, Stylize},
+    text::{Line, Span},
     layout::Rect,
     Frame
 };
 
 use crate::{app::App, key_event::get_content};
 
+use super::utils::highlight_line;
+
 pub struct CompletionPopup<'a> {
-    candidates: &'a Vec<Cow<'a, str>>
+    items: Vec<ListItem<'a>>,
+    titles: Option<(Line<'a>, Line<'a>)>,
 }
 
 impl<'a> CompletionPopup<'a> {
     pub fn new(candidates: &'a Vec<Cow<'a, str>>) -> Self {
-        Self { candidates }
+        Self::from_items(
+            candidates.iter()
+                .map(|_candidate| ListItem::new(_candidate.as_ref()))
+                .collect()
+        )
+    }
+
+    pub fn from_items(items: Vec<ListItem<'a>>) -> Self {
+        Self { items, titles: None }
+    }
+
+    /// Set the titles at the top & bottom of popup.
+    pub fn titles(mut self, top: Line<'a>, bottom: Line<'a>) -> Self {
+        self.titles = Some((top, bottom));
+        self
     }
 }
 
@@ -33,14 +51,13 @@ impl StatefulWidget for CompletionPopup<'_> {
     {
         Clear.render(area, buf);
 
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL);
+        if let Some((top, bottom)) = self.titles {
+            block = block.title_top(top).title_bottom(bottom);
+        }
 
-        let completion_list = List::new(
-            self.candidates.iter()
-                .map(|_candidate| ListItem::new(_candidate.as_ref()))
-                .collect::<Vec<_>>()
-        )
+        let completion_list = List::new(self.items)
             .highlight_style(Style::default().white().reversed())
             .block(block);
 
@@ -99,6 +116,62 @@ pub fn render_completion(
     );
 }
 
+/// Render the popup of finder at the center of frame.
+pub fn render_finder(app: &mut App, frame: &mut Frame) {
+    let Some(ref mut finder) = app.finder else {
+        return
+    };
+
+    let frame_area = frame.area();
+    let width = frame_area.width * 7 / 10;
+    let height = frame_area.height * 6 / 10;
+    let area = Rect {
+        x: (frame_area.width - width) / 2,
+        y: (frame_area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let top = Line::from(vec![
+        Span::raw(format!(" Find: {}", finder.query())),
+        Span::raw(" ").bg(Color::White),
+        Span::raw(" "),
+    ]);
+
+    let (matched, total) = finder.count();
+    let bottom = Line::raw(format!(
+        " {}/{}{} ",
+        matched,
+        total,
+        if finder.walking() { " ..." } else { "" }
+    )).right_aligned();
+
+    let colors = &app.term_colors;
+    let (items, selected) = finder.window(height.saturating_sub(2) as usize);
+    let items = items.into_iter()
+        .map(|(path, positions)| {
+            let style = if path.ends_with('/') {
+                colors.dir_style
+            } else {
+                colors.file_style
+            };
+
+            ListItem::new(highlight_line(path, positions)).set_style(style)
+        })
+        .collect::<Vec<_>>();
+
+    let mut state = ListState::default();
+    if !items.is_empty() {
+        state.select(Some(selected));
+    }
+
+    frame.render_stateful_widget(
+        CompletionPopup::from_items(items).titles(top, bottom),
+        area,
+        &mut state
+    );
+}
+
 fn area_minus(_num: &mut u16, rhs: u16) {
     if rhs > *_num {
         return ()
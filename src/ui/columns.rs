@@ -0,0 +1,113 @@
+// Columns
+
+use std::path::Path;
+
+use ratatui::{style::Stylize, text::{Line, Span}};
+
+use crate::app::{App, FileSaver};
+use crate::config::{Column, Config, ConfigValue};
+use crate::utils::{group_name, user_name};
+
+/// Get the columns set in user_config.toml.
+pub fn get_columns<'a>(app: &'a App) -> &'a [Column] {
+    match Config::get_value(&app.config, "columns") {
+        ConfigValue::Columns(columns) => columns.as_slice(),
+        _ => &[]
+    }
+}
+
+/// Create the text of columns for each of FILES in DIR, cells of a column are aligned.
+///
+/// Columns are dropped from the last one until the longest name can be shown fully in WIDTH,
+/// where RESERVED is taken by borders & other info at the right side.
+pub fn render_columns<'a>(
+    app: &App,
+    files: &[FileSaver],
+    dir: &Path,
+    width: u16,
+    reserved: u16
+) -> Option<Vec<Line<'a>>>
+{
+    let columns = get_columns(app);
+    if columns.is_empty() || files.is_empty() {
+        return None
+    }
+
+    let cells: Vec<Vec<String>> = files.iter()
+        .map(|file| columns.iter()
+             .map(|column| cell_text(app, column, dir, file))
+             .collect())
+        .collect();
+
+    // Empty columns like `link` without any symlink are skipped.
+    let mut widths: Vec<usize> = (0..columns.len())
+        .map(|idx| cells.iter()
+             .map(|row| Span::raw(row[idx].as_str()).width())
+             .max()
+             .unwrap_or(0))
+        .collect();
+
+    let name_width = files.iter()
+        .map(|file| Span::raw(file.name.as_str()).width())
+        .max()
+        .unwrap_or(0);
+    let available = (width as usize).saturating_sub(name_width + reserved as usize);
+
+    // Each column is preceded by a space.
+    while widths.iter().map(|width| if *width > 0 { width + 1 } else { 0 }).sum::<usize>() > available {
+        widths.pop();
+    }
+    if widths.iter().all(|width| *width == 0) {
+        return None
+    }
+
+    let lines = cells.into_iter()
+        .map(|row| {
+            let spans: Vec<Span> = row.into_iter()
+                .zip(columns.iter())
+                .zip(widths.iter())
+                .filter(|(_, width)| **width > 0)
+                .map(|((text, column), width)| {
+                    let text = match column {
+                        Column::Size | Column::Count => format!(" {:>width$}", text),
+                        _ => format!(" {:<width$}", text),
+                    };
+
+                    Span::raw(text).dark_gray()
+                })
+                .collect();
+
+            Line::from(spans)
+        })
+        .collect();
+
+    Some(lines)
+}
+
+fn cell_text(app: &App, column: &Column, dir: &Path, file: &FileSaver) -> String {
+    match column {
+        Column::Size => {
+            let size = app.dir_size.total(dir, file).unwrap_or(file.size());
+            file_size::fit_4(size)
+        },
+
+        Column::Modified(format) => file.modified_text(format),
+        Column::Permissions => file.mode_text(),
+
+        Column::Octal => file.mode()
+            .map(|mode| format!("{:03o}", mode))
+            .unwrap_or_default(),
+
+        Column::Owner => file.owner()
+            .map(|(uid, gid)| format!("{}:{}", user_name(uid), group_name(gid)))
+            .unwrap_or_default(),
+
+        Column::Link => file.symlink_file.as_ref()
+            .map(|target| format!("-> {}", target.to_string_lossy()))
+            .unwrap_or_default(),
+
+        Column::Count => file.item_count
+            .map(|count| count.to_string())
+            .unwrap_or_default(),
+    }
+}
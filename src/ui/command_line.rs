@@ -3,14 +3,17 @@
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
-    widgets::{Block, Paragraph, Widget},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Widget},
     text::{Line, Span},
     Frame,
 };
 
 use crate::app::App;
+use crate::key_event::SearchMode;
 use crate::utils::{CmdContent, CursorPos};
 
+use super::utils::highlight_line;
+
 /// The widget to show states, such as file permission, size, etc.
 pub struct StateLine<'a> {
     left_side: Line<'a>,
@@ -83,40 +86,95 @@ pub fn render_command_line<'a>(
                         app.term_colors.orphan_style
                     ).alignment(Alignment::Left));
                 } else {
-                    lines.push(Line::from(vec![
-                        selected_file.permission_span(),
+                    let mut spans = selected_file.permission_spans();
+                    spans.extend([
                         Span::raw(" "),
                         selected_file.modified_span(),
                         Span::raw(" "),
-                        selected_file.size_span(),
+                        app.dir_size.size_span(&app.path, selected_file),
                         Span::raw(" "),
                         selected_file.symlink_span(app.term_colors.symlink_style)
-                    ]).alignment(Alignment::Left));
+                    ]);
+                    lines.push(Line::from(spans).alignment(Alignment::Left));
                 }
             } else {
                 lines.push(Line::raw("").alignment(Alignment::Left));
             }
 
+            let mut right_side: Vec<Span> = Vec::new();
+
+            // The progress of background jobs
+            let mut jobs = app.jobs.iter();
+            if let Some(job) = jobs.next() {
+                let rest = jobs.count();
+                right_side.push(Span::raw(job.summary()).cyan());
+
+                if rest > 0 {
+                    right_side.push(Span::raw(format!(" (+{})", rest)).cyan());
+                }
+            }
+
             if app.mark_expand {
                 let mut style = app.term_colors.marked_style;
                 if style.bg.is_some() {
                     style.bg = None;
                 }
 
-                lines.push(Line::styled(
+                if !right_side.is_empty() {
+                    right_side.push(Span::raw(" "));
+                }
+                right_side.push(Span::styled(
                     "EXPAND",
                     style.add_modifier(Modifier::BOLD)
-                ).alignment(Alignment::Right));
+                ));
             } else if app.quit_after_output {
                 let mut style = app.term_colors.symlink_style;
                 if style.bg.is_some() {
                     style.bg = None;
                 }
 
-                lines.push(Line::styled(
+                if !right_side.is_empty() {
+                    right_side.push(Span::raw(" "));
+                }
+                right_side.push(Span::styled(
                     "QUIT",
                     style.add_modifier(Modifier::BOLD)
-                ).alignment(Alignment::Right));
+                ));
+            }
+
+            if app.dir_size.usage_mode() {
+                if !right_side.is_empty() {
+                    right_side.push(Span::raw(" "));
+                }
+                right_side.push(Span::raw("USAGE").cyan().bold());
+            }
+
+            if app.file_searcher.mode != SearchMode::default() {
+                if !right_side.is_empty() {
+                    right_side.push(Span::raw(" "));
+                }
+                right_side.push(Span::raw(
+                    app.file_searcher.mode.name().to_uppercase()
+                ).yellow().bold());
+            }
+
+            if let Some(register) = app.macros.recording() {
+                if !right_side.is_empty() {
+                    right_side.push(Span::raw(" "));
+                }
+                right_side.push(Span::raw(format!("REC @{}", register)).red().bold());
+            }
+
+            // The keys waiting for the rest of key sequence
+            if !app.pending_keys.is_empty() {
+                if !right_side.is_empty() {
+                    right_side.push(Span::raw(" "));
+                }
+                right_side.push(Span::raw(app.pending_keys.to_string()).bold());
+            }
+
+            if !right_side.is_empty() {
+                lines.push(Line::from(right_side).alignment(Alignment::Right));
             }
 
             frame.render_widget(
@@ -129,6 +187,11 @@ pub fn render_command_line<'a>(
             let block = Block::default();
             let para = if let CmdContent::Text(text) = input {
                 Paragraph::new(text.to_owned())
+            } else if let Some(ref search) = app.history_search {
+                Paragraph::new(Line::from(vec![
+                    Span::raw(search.prompt()),
+                    Span::raw(input.get_str())
+                ]))
             } else {
                 Paragraph::new(Line::from(
                     get_command_line_span_list(
@@ -141,10 +204,75 @@ pub fn render_command_line<'a>(
             .block(block);
 
             frame.render_widget(para, area);
+
+            // The search mode of input
+            let search = match input {
+                CmdContent::String(content) if cursor != CursorPos::None &&
+                    app.history_search.is_none() => content.strip_prefix('/'),
+                _ => None
+            };
+            if let Some(search) = search {
+                let (mode, _) = SearchMode::split_prefix(search, app.file_searcher.mode);
+                frame.render_widget(
+                    Line::raw(format!("[{}]", mode.name()))
+                        .dark_gray()
+                        .alignment(Alignment::Right),
+                    area
+                );
+            }
         }
     }
 }
 
+/// Render the results of grep in the expanded command line.
+pub fn render_grep(app: &mut App, frame: &mut Frame, area: Rect) {
+    let Some(ref mut grep) = app.grep else {
+        return
+    };
+
+    let header = Line::from(vec![
+        Span::raw("[Grep] "),
+        Span::raw(grep.query().to_owned()).bold(),
+        Span::raw(format!(
+            "  {} results{}",
+            grep.count(),
+            if grep.searching() { " ..." } else { "" }
+        )).dark_gray(),
+    ]);
+    frame.render_widget(header, area);
+
+    let list_area = Rect {
+        y: area.y + 1,
+        height: area.height.saturating_sub(1),
+        ..area
+    };
+
+    let colors = &app.term_colors;
+    let (results, selected) = grep.window(list_area.height as usize);
+    let items = results.iter()
+        .map(|result| {
+            let mut line = Line::from(vec![
+                Span::styled(result.path.to_owned(), colors.file_style),
+                Span::raw(format!(":{}: ", result.line)).dark_gray(),
+            ]);
+            line.extend(highlight_line(&result.snippet, &result.positions));
+
+            ListItem::new(line)
+        })
+        .collect::<Vec<_>>();
+
+    let mut state = ListState::default();
+    if !items.is_empty() {
+        state.select(Some(selected));
+    }
+
+    frame.render_stateful_widget(
+        List::new(items).highlight_style(Style::default().reversed()),
+        list_area,
+        &mut state
+    );
+}
+
 /// Create Paragraph structure with different color.
 ///
 /// Make the text red when it's an error message.
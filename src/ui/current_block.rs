@@ -9,7 +9,11 @@ use ratatui::{
 
 use crate::app::App;
 
-use super::{list::List, utils::{render_editing_list, render_list}};
+use super::{
+    columns::render_columns,
+    list::List,
+    utils::{render_editing_list, render_list}
+};
 
 pub fn render_current(app: &mut App, frame: &mut Frame, area: Rect) {
     let border_set = Set {
@@ -33,6 +37,7 @@ pub fn render_current(app: &mut App, frame: &mut Frame, area: Rect) {
         app.marked_files.get(&app.path)
     };
 
+    let current_path = app.current_path();
     let (current_items, marked) = if app.edit_mode.enabled {
         render_editing_list(
             &app.edit_mode,
@@ -43,8 +48,22 @@ pub fn render_current(app: &mut App, frame: &mut Frame, area: Rect) {
         render_list(
             app.current_files.iter(),
             &app.term_colors,
-            marked_items
-        )   
+            marked_items,
+            if app.root() {
+                None
+            } else {
+                app.file_searcher.highlights(&app.path)
+            },
+            app.git_status.repo().map(|repo| (repo, current_path.as_path())),
+            app.dir_size.usage_mode().then_some((&app.dir_size, current_path.as_path())),
+            render_columns(
+                app,
+                &app.current_files,
+                &current_path,
+                area.width,
+                reserved_width(app)
+            )
+        )
     };
 
     frame.render_stateful_widget(
@@ -63,3 +82,19 @@ pub fn render_current(app: &mut App, frame: &mut Frame, area: Rect) {
         &mut app.selected_item.current
     );
 }
+
+/// The width taken by the border, sidebar, navigation index and other info at the right side.
+fn reserved_width(app: &App) -> u16 {
+    let mut width = 2 + app.current_files.len().to_string().len() as u16;
+
+    if app.git_status.repo().is_some() {
+        width += 2;
+    }
+
+    // The size with bar of disk usage mode.
+    if app.dir_size.usage_mode() {
+        width += 18;
+    }
+
+    width
+}
@@ -194,7 +194,7 @@ impl StatefulWidget for List<'_> {
             }
 
 
-            let mut right = Line::default();
+            let mut right = item.right.take().unwrap_or_default();
 
             // Set Index for each item
             if self.navi_show {
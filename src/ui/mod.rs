@@ -2,6 +2,7 @@
 
 mod list;
 mod utils;
+mod columns;
 mod child_block;
 mod command_line;
 mod parent_block;
@@ -21,15 +22,16 @@ use crate::{app::App, utils::{self as cutils, CursorPos, CmdContent}};
 use command_line::*;
 use parent_block::render_parent;
 use current_block::render_current;
-use cmdline_popup::render_completion;
+use cmdline_popup::{render_completion, render_finder};
 use child_block::{render_child, render_file};
 
 pub use child_block::update_file_linenr;
 
 pub fn ui(frame: &mut Frame, app: &mut App) -> anyhow::Result<()> {
+    let grep_shown = app.grep.as_ref().is_some_and(|grep| grep.shown());
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(if !app.command_expand {
+        .constraints(if !app.command_expand && !grep_shown {
             vec![
                 Constraint::Percentage(4),
                 Constraint::Percentage(93),
@@ -96,6 +98,13 @@ pub fn ui(frame: &mut Frame, app: &mut App) -> anyhow::Result<()> {
         .alignment(Alignment::Right)
         .block(item_info_block);
 
+    // Results of grep
+    if grep_shown {
+        frame.render_widget(computer_info, chunks[0]);
+        render_grep(app, frame, chunks[1]);
+        return Ok(())
+    }
+
     // Expanded Commandline
     if app.command_expand {
         let command_block = Block::default();
@@ -172,6 +181,7 @@ pub fn ui(frame: &mut Frame, app: &mut App) -> anyhow::Result<()> {
     // Command Block
     render_command_line(app, frame, chunks[2]);
     render_completion(app, frame, chunks[2]);
+    render_finder(app, frame);
 
     Ok(())
 }
@@ -241,8 +251,28 @@ fn get_title_right_info(app: &App) -> (Paragraph<'static>, usize) {
         }
     );
 
-    let length = item_index.len() + tab_index.len();
+    // The filter of current directory
+    let filter = match app.file_filter {
+        Some(ref filter) if filter.applies(&app.path) => format!("[{}] ", filter.input()),
+        _ => String::new()
+    };
+
+    // The branch of git repository, with commits ahead & behind upstream
+    let branch = match app.git_status.repo() {
+        Some(repo) => match repo.ahead_behind() {
+            Some((ahead, behind)) if ahead > 0 || behind > 0 => {
+                format!("{}[↑{}↓{}] ", repo.branch(), ahead, behind)
+            },
+            _ => format!("{} ", repo.branch())
+        },
+        None => String::new()
+    };
+
+    let length = branch.chars().count() + filter.chars().count() +
+        item_index.len() + tab_index.len();
     let mut line = Line::default();
+    line.push_span(Span::raw(branch).magenta());
+    line.push_span(Span::raw(filter).yellow());
     line.push_span(Span::raw(tab_index).bold());
     line.push_span(Span::raw(item_index));
 
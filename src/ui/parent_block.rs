@@ -22,10 +22,24 @@ pub fn render_parent(app: &mut App, frame: &mut Frame, area: Rect) {
         }
     };
 
+    let parent_path = if app.root() {
+        Some(app.path.as_path())
+    } else {
+        app.path.parent()
+    };
+
     let (parent_items, marked) = render_list(
         app.parent_files.iter(),
         &app.term_colors,
-        marked_files
+        marked_files,
+        if app.root() {
+            app.file_searcher.highlights(&app.path)
+        } else {
+            None
+        },
+        app.git_status.repo().zip(parent_path),
+        None,
+        None
     );
 
     frame.render_stateful_widget(
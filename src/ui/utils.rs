@@ -1,6 +1,7 @@
 // Utils
 
 use std::collections::HashMap;
+use std::path::Path;
 
 use ratatui::{
     style::{Color, Modifier, Style, Styled, Stylize},
@@ -10,16 +11,25 @@ use ratatui::{
 use crate::{
     key_event::{EditItem, EditMode},
     utils::{CursorPos, MarkedFiles},
-    app::{FileSaver, TermColors},
+    app::{DirSize, FileSaver, GitRepo, GitState, TermColors},
 };
 
 use super::list::Item;
 
 /// Create a list of ListItem
+///
+/// HIGHLIGHTS are positions of characters matched by search, indexed by file.
+/// GIT is the repository with the directory of files, to show their git status.
+/// USAGE is given with the directory of files in disk usage mode, to show bars of total size.
+/// COLUMNS are the texts of columns for each file.
 pub fn render_list<'a>(
     files: std::slice::Iter<'a, FileSaver>,
     colors: &TermColors,
     marked_items: Option<&'a MarkedFiles>,
+    highlights: Option<&HashMap<usize, Vec<usize>>>,
+    git: Option<(&GitRepo, &Path)>,
+    usage: Option<(&DirSize, &Path)>,
+    columns: Option<Vec<Line<'a>>>,
 ) -> (Vec<Item<'a>>, bool)
 {
     let mut temp_items: Vec<Item> = Vec::new();
@@ -36,10 +46,22 @@ pub fn render_list<'a>(
         &temp_set
     };
 
-    for file in files {
+    // Bars are proportional to the largest file.
+    let largest = usage.and_then(|(dir_size, dir)| files.as_slice()
+        .iter()
+        .filter_map(|file| dir_size.total(dir, file))
+        .max());
+
+    let mut columns = columns.map(Vec::into_iter);
+
+    for (idx, file) in files.enumerate() {
         temp_items.push(get_normal_item_color(
             file,
             colors,
+            highlights.and_then(|highlights| highlights.get(&idx)),
+            git.and_then(|(repo, dir)| repo.state(&dir.join(&file.name))),
+            usage.map(|(dir_size, dir)| (dir_size.total(dir, file), largest.unwrap_or(0))),
+            columns.as_mut().and_then(Iterator::next),
             if marked_files.contains_key(&file.name) {
                 if !marked {
                     marked = true;
@@ -151,6 +173,10 @@ fn get_editing_item_color<'a>(
 fn get_normal_item_color<'a>(
     file: &'a FileSaver,
     colors: &TermColors,
+    highlight: Option<&Vec<usize>>,
+    git_state: Option<GitState>,
+    usage: Option<(Option<u64>, u64)>,
+    columns: Option<Line<'a>>,
     marked: bool
 ) -> Item<'a>
 {
@@ -166,10 +192,104 @@ fn get_normal_item_color<'a>(
         colors.file_style
     };
 
-    Item::new::<&str>(&file.name, None).set_style(style)
+    let name = if let Some(positions) = highlight {
+        highlight_line(&file.name, positions)
+    } else {
+        Line::raw(file.name.as_str())
+    };
+
+    // Ignored files are dimmed.
+    let style = if git_state == Some(GitState::Ignored) {
+        style.add_modifier(Modifier::DIM)
+    } else {
+        style
+    };
+
+    // Info at the right side is separated with spaces.
+    let right = [
+        columns,
+        usage.map(|(size, largest)| usage_line(size, largest)),
+        git_state.map(git_line),
+    ].into_iter()
+        .flatten()
+        .reduce(|mut line, part| {
+            line.push_span(Span::raw(" "));
+            line.extend(part);
+            line
+        });
+
+    Item::new(name, right).set_style(style)
         .sidebar(if marked {
             Some(colors.marked_style.add_modifier(Modifier::REVERSED))
         } else {
             None
         })
 }
+
+/// The mark of git status shown at the right side of file.
+fn git_line<'a>(state: GitState) -> Line<'a> {
+    let span = Span::raw(state.symbol());
+    let span = match state {
+        GitState::Ignored    => span.dark_gray(),
+        GitState::Untracked  => span.magenta(),
+        GitState::Staged     => span.green(),
+        GitState::Modified   => span.yellow(),
+        GitState::Conflicted => span.red().bold(),
+    };
+
+    Line::from(span)
+}
+
+/// The total size with a bar proportional to the LARGEST one, SIZE is None while computing.
+fn usage_line<'a>(size: Option<u64>, largest: u64) -> Line<'a> {
+    const BAR_WIDTH: u64 = 10;
+
+    let Some(size) = size else {
+        return Line::from(vec![
+            Span::raw(format!("{:>4} [", "...")),
+            Span::raw(" ".repeat(BAR_WIDTH as usize)),
+            Span::raw("]"),
+        ]).dark_gray()
+    };
+
+    let filled = if largest == 0 {
+        0
+    } else {
+        (size * BAR_WIDTH).div_ceil(largest)
+    };
+
+    Line::from(vec![
+        Span::raw(format!("{:>4} [", file_size::fit_4(size))),
+        Span::raw("#".repeat(filled as usize)).cyan(),
+        Span::raw(" ".repeat((BAR_WIDTH - filled) as usize)),
+        Span::raw("]"),
+    ])
+}
+
+/// Make the characters at POSITIONS of NAME eye-catching.
+pub fn highlight_line<'a>(name: &str, positions: &[usize]) -> Line<'a> {
+    let mut line = Line::default();
+    let mut chunk = String::new();
+    let mut chunk_matched = false;
+
+    for (idx, c) in name.chars().enumerate() {
+        let matched = positions.contains(&idx);
+        if matched != chunk_matched && !chunk.is_empty() {
+            line.push_span(highlight_span(std::mem::take(&mut chunk), chunk_matched));
+        }
+
+        chunk.push(c);
+        chunk_matched = matched;
+    }
+    line.push_span(highlight_span(chunk, chunk_matched));
+
+    line
+}
+
+fn highlight_span<'a>(content: String, matched: bool) -> Span<'a> {
+    if matched {
+        Span::raw(content).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    } else {
+        Span::raw(content)
+    }
+}
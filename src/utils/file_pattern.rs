@@ -0,0 +1,76 @@
+// File Pattern
+
+use std::path::Path;
+
+use anyhow::Result;
+use globset::{GlobBuilder, GlobMatcher};
+
+/// The pattern used to match files by name or type.
+#[derive(Clone)]
+pub enum FilePattern {
+    /// Match file name with glob.
+    Glob(GlobMatcher),
+
+    /// Match MIME type guessed from extension, such as "application/pdf" or "video/*".
+    Mime(String),
+
+    /// Match extension without case sensitivity.
+    Extension(String),
+}
+
+impl FilePattern {
+    /// Patterns containing '/' are regarded as MIME types, others are globs.
+    pub fn new(pattern: &str) -> Result<Self> {
+        if pattern.contains('/') {
+            return Ok(Self::Mime(pattern.to_owned()))
+        }
+
+        Self::glob(pattern)
+    }
+
+    pub fn glob(pattern: &str) -> Result<Self> {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()?;
+
+        Ok(Self::Glob(glob.compile_matcher()))
+    }
+
+    pub fn extension(extension: &str) -> Self {
+        Self::Extension(extension.trim_start_matches('.').to_lowercase())
+    }
+
+    pub fn is_match(&self, path: &Path) -> bool {
+        match self {
+            Self::Glob(matcher) => path.file_name()
+                .is_some_and(|name| matcher.is_match(name)),
+
+            Self::Mime(mime) => mime_guess::from_path(path).iter().any(|guess| {
+                let guess = guess.essence_str();
+                if let Some(prefix) = mime.strip_suffix("/*") {
+                    guess.split('/').next() == Some(prefix)
+                } else {
+                    guess == mime
+                }
+            }),
+
+            Self::Extension(extension) => path.extension()
+                .is_some_and(|ext| ext.to_string_lossy().to_lowercase() == *extension),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_pattern() {
+        assert!(FilePattern::new("video/*").unwrap().is_match(Path::new("a.mp4")));
+        assert!(FilePattern::new("application/pdf").unwrap().is_match(Path::new("/x/a.pdf")));
+        assert!(FilePattern::new("*.{tar,tgz}").unwrap().is_match(Path::new("/x/a.tgz")));
+        assert!(!FilePattern::new("*.tar").unwrap().is_match(Path::new("/x/a.tgz")));
+        assert!(FilePattern::extension("PNG").is_match(Path::new("a.png")));
+        assert!(!FilePattern::extension("png").is_match(Path::new("png")));
+    }
+}
@@ -0,0 +1,100 @@
+// Syntax Highlight
+
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{FontStyle, ThemeSet},
+    parsing::{SyntaxReference, SyntaxSet},
+    util::LinesWithEndings,
+};
+
+/// Files larger than this size are shown without highlighting.
+pub const HIGHLIGHT_SIZE_LIMIT: u64 = 1024 * 1024;
+
+lazy_static! {
+    static ref SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_newlines();
+    static ref THEME_SET: ThemeSet = ThemeSet::load_defaults();
+}
+
+/// Highlight CONTENT of file at PATH with THEME.
+/// Return None when the theme or syntax cannot be found.
+pub fn highlight<'a>(content: &str, path: &Path, theme: &str) -> Option<Text<'a>> {
+    let theme = THEME_SET.themes.get(theme)?;
+    let syntax = find_syntax(content, path)?;
+
+    let mut text = Text::default();
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    for line in LinesWithEndings::from(content) {
+        let mut spans: Vec<Span> = Vec::new();
+
+        for (style, piece) in highlighter.highlight_line(line, &SYNTAX_SET).ok()? {
+            let piece = piece.trim_end_matches(['\n', '\r']);
+            if piece.is_empty() {
+                continue;
+            }
+
+            let mut span_style = Style::default().fg(Color::Rgb(
+                style.foreground.r,
+                style.foreground.g,
+                style.foreground.b
+            ));
+
+            if style.font_style.contains(FontStyle::BOLD) {
+                span_style = span_style.add_modifier(Modifier::BOLD);
+            }
+            if style.font_style.contains(FontStyle::ITALIC) {
+                span_style = span_style.add_modifier(Modifier::ITALIC);
+            }
+            if style.font_style.contains(FontStyle::UNDERLINE) {
+                span_style = span_style.add_modifier(Modifier::UNDERLINED);
+            }
+
+            spans.push(Span::styled(piece.replace("\t", "    "), span_style));
+        }
+
+        text.push_line(Line::from(spans));
+    }
+
+    Some(text)
+}
+
+/// Find syntax by file name, extension and then the shebang line.
+/// Plain text is regarded as no syntax.
+fn find_syntax(content: &str, path: &Path) -> Option<&'static SyntaxReference> {
+    let file_name = path.file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let extension = path.extension()
+        .map(|ext| ext.to_string_lossy())
+        .unwrap_or_default();
+
+    let syntax = SYNTAX_SET.find_syntax_by_extension(&file_name)
+        .or_else(|| SYNTAX_SET.find_syntax_by_extension(&extension))
+        .or_else(|| SYNTAX_SET.find_syntax_by_first_line(content.lines().next()?))?;
+
+    if syntax.name == "Plain Text" {
+        return None
+    }
+
+    Some(syntax)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_syntax() {
+        assert_eq!(find_syntax("", Path::new("main.rs")).unwrap().name, "Rust");
+        assert_eq!(find_syntax("", Path::new("Makefile")).unwrap().name, "Makefile");
+        assert_eq!(find_syntax("#!/bin/bash\n", Path::new("run")).unwrap().name, "Bourne Again Shell (bash)");
+        assert!(find_syntax("hello", Path::new("note.txt")).is_none());
+    }
+}
@@ -1,8 +1,15 @@
 // Utils for crate.
 
 mod types;
+mod highlight;
+mod file_pattern;
+mod owner;
 
-use std::{borrow::Cow, fs::File, io::Read, sync::atomic::{AtomicU16, Ordering}};
+#[cfg(test)]
+mod test_dir;
+
+use std::{borrow::Cow, io::Read, sync::atomic::{AtomicU16, Ordering}};
+use std::path::{Path, PathBuf};
 
 use anyhow::bail;
 use clap::Parser;
@@ -11,6 +18,12 @@ use ansi_to_tui::IntoText;
 use lazy_static::lazy_static;
 
 pub use types::*;
+pub use highlight::HIGHLIGHT_SIZE_LIMIT;
+pub use file_pattern::FilePattern;
+pub use owner::{group_id, group_name, user_id, user_name};
+
+#[cfg(test)]
+pub use test_dir::TestDir;
 
 lazy_static! {
     /// The height of file list & content preview windows.
@@ -78,19 +91,48 @@ pub fn str_split(_string: String) -> Vec<String> {
     str_vec
 }
 
-/// Read limited lines from file, and pass content as `Text` structure to `text_ref`.
+/// Get a path that doesn't exist by appending number suffix to the file stem of PATH.
+pub fn unique_path(path: PathBuf) -> PathBuf {
+    if path.symlink_metadata().is_err() {
+        return path
+    }
+
+    let parent = path.parent().unwrap_or(Path::new("/"));
+    let stem = path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path.extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut idx = 1;
+    loop {
+        let new_path = parent.join(format!("{}_{}{}", stem, idx, extension));
+        if new_path.symlink_metadata().is_err() {
+            return new_path
+        }
+
+        idx += 1;
+    }
+}
+
+/// Read limited lines from file or command output, and pass content as `Text` structure to `text_ref`.
 /// In the meanwhile, the newline character of Windows will be removed
 /// and the '\t' will be replaced with 4 spaces.
+///
+/// When SYNTAX is some, the content without ANSI escape sequences is highlighted
+/// with the theme according to the file path.
 pub fn read_to_text(
     text_ref: &mut Text,
-    file: &File,
+    reader: impl Read,
+    syntax: Option<(&Path, &str)>
 ) -> anyhow::Result<()>
 {
     let line_nr = get_window_height();
 
     let mut idx = 1;
     let mut bytes: Vec<u8> = Vec::new();
-    for _b in file.bytes() {
+    for _b in reader.bytes() {
         let byte = _b?;
         if byte != 13 {
             // To limit content read from file
@@ -107,6 +149,16 @@ pub fn read_to_text(
     }
 
     let _string = String::from_utf8(bytes)?;
+
+    let highlighted = syntax
+        .filter(|_| !_string.contains('\x1b'))
+        .and_then(|(path, theme)| highlight::highlight(&_string, path, theme));
+
+    if let Some(text) = highlighted {
+        *text_ref = text;
+        return Ok(())
+    }
+
     let mut text = _string.into_text()?;
 
     for line in text.iter_mut() {
@@ -0,0 +1,75 @@
+// Names of users & groups
+
+use std::fs;
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref USERS: HashMap<u32, String> = read_names("/etc/passwd");
+    static ref GROUPS: HashMap<u32, String> = read_names("/etc/group");
+}
+
+/// Get the name of user with UID, or the number when it's unknown.
+pub fn user_name(uid: u32) -> String {
+    USERS.get(&uid).cloned().unwrap_or_else(|| uid.to_string())
+}
+
+/// Get the name of group with GID, or the number when it's unknown.
+pub fn group_name(gid: u32) -> String {
+    GROUPS.get(&gid).cloned().unwrap_or_else(|| gid.to_string())
+}
+
+/// Get the id of user NAME, which can be the number as well.
+pub fn user_id(name: &str) -> Option<u32> {
+    name.parse().ok().or_else(|| find_id(&USERS, name))
+}
+
+/// Get the id of group NAME, which can be the number as well.
+pub fn group_id(name: &str) -> Option<u32> {
+    name.parse().ok().or_else(|| find_id(&GROUPS, name))
+}
+
+fn find_id(names: &HashMap<u32, String>, name: &str) -> Option<u32> {
+    names.iter()
+        .find(|(_, _name)| _name.as_str() == name)
+        .map(|(id, _)| *id)
+}
+
+/// Read ids & names from a file in the format of /etc/passwd, like `name:x:id:...`.
+fn read_names(path: &str) -> HashMap<u32, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return HashMap::new()
+    };
+
+    parse_names(&content)
+}
+
+fn parse_names(content: &str) -> HashMap<u32, String> {
+    content.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+
+            Some((id, name.to_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_names() {
+        let names = parse_names(
+            "# comment\nroot:x:0:0:root:/root:/bin/bash\nspring:x:1000:1000::/home/spring:/bin/zsh\nbroken\n"
+        );
+
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[&0], "root");
+        assert_eq!(names[&1000], "spring");
+    }
+}
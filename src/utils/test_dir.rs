@@ -0,0 +1,53 @@
+// Temporary Directory for Tests
+
+use std::{env, fs, io, process};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A unique directory for a test, which is removed when dropped even if the test fails.
+pub struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    /// Create `hire_<NAME>_test_<pid>_<count>` in temporary directory.
+    pub fn new(name: &str) -> Self {
+        loop {
+            let path = env::temp_dir().join(format!(
+                "hire_{}_test_{}_{}",
+                name,
+                process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+
+            match fs::create_dir(&path) {
+                Ok(_) => return Self { path },
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => panic!("Cannot create test directory: {}", err)
+            }
+        }
+    }
+}
+
+impl Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TestDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}